@@ -0,0 +1,66 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+use wasm_lang_inference::{classify, matching_rule, parse_wasm, RULES};
+
+// Compares the full rule chain with matching_rule (which pre-screens the imports first), and times classify end to
+// end, which adds the hints and confidence to the rule that matched. Runs over the directory given
+// by `cargo bench -- <dir>`, or ./wasm when it has been downloaded, otherwise the test fixtures - which are tiny, so
+// don't show the benefit of the pre-screen on modules with hundreds of imports.
+fn main() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let dir = match env::args().skip(1).find(|arg| !arg.starts_with("--")) {
+        Some(dir) => PathBuf::from(dir),
+        None if root.join("wasm").is_dir() => root.join("wasm"),
+        None => root.join("tests").join("fixtures"),
+    };
+
+    let buffers: Vec<Vec<u8>> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+        .map(|path| fs::read(path).unwrap())
+        .collect();
+    let modules: Vec<_> = buffers.iter().map(|buf| parse_wasm(buf)).collect();
+    let iterations = 1_000_000 / modules.len().max(1);
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        for module in &modules {
+            std::hint::black_box(RULES.iter().find(|rule| rule.matches(module)));
+        }
+    }
+    let full_chain = start.elapsed();
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        for module in &modules {
+            std::hint::black_box(matching_rule(module));
+        }
+    }
+    let prescreened = start.elapsed();
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        for module in &modules {
+            std::hint::black_box(classify(module));
+        }
+    }
+    let classify_time = start.elapsed();
+
+    let classified = (iterations * modules.len()) as f64;
+    println!("{} modules from {}", modules.len(), dir.display());
+    println!(
+        "full chain:  {:.0} ns / module",
+        full_chain.as_nanos() as f64 / classified
+    );
+    println!(
+        "pre-screen:  {:.0} ns / module",
+        prescreened.as_nanos() as f64 / classified
+    );
+    println!(
+        "classify:    {:.0} ns / module",
+        classify_time.as_nanos() as f64 / classified
+    );
+}
@@ -0,0 +1,23 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wasm_lang_inference::{
+    classify, extract_embedded, gzip, infer_language_prefix, parse_wasm_with, ParseOptions,
+};
+
+// Modules come from the web, so anything can turn up in the corpus. Parsing never fails - malformed sections just
+// leave a partial module - so the only thing to check is that nothing on the way to a classification panics,
+// including the code scan, the DWARF reader and the preprocessing that --auto-detect and --extract-embedded do.
+fuzz_target!(|data: &[u8]| {
+    let options = ParseOptions {
+        scan_code: true,
+        deep: true,
+        ..ParseOptions::default()
+    };
+    let module = parse_wasm_with(data, &options);
+    classify(&module);
+    module.fingerprint();
+    infer_language_prefix(data);
+    extract_embedded(data);
+    gzip::gunzip(data);
+});
@@ -0,0 +1,40 @@
+/*
+ * A sample plugin, which classifies modules importing from a `webjs` module (such as the Pascal-compiled TikZJax
+ * module that the built-in rules leave as unknown) as hand-written.
+ *
+ *   % cc -shared -fPIC -o libsample.so plugins/sample.c
+ *   % cargo run --features plugins -- --plugin ./libsample.so
+ */
+#include <string.h>
+
+#include "wasm_lang_inference.h"
+
+static bool str_eq(wli_str s, const char *expected) {
+    return s.len == strlen(expected) && memcmp(s.ptr, expected, s.len) == 0;
+}
+
+static bool imports_webjs(const wli_module *module) {
+    for (size_t i = 0; i < module->import_count; i++) {
+        if (str_eq(module->import_modules[i], "webjs")) {
+            return true;
+        }
+    }
+    return false;
+}
+
+static const wli_detector detectors[] = {
+    {
+        .id = "sample.webjs",
+        .language = "handwritten",
+        .description = "imports from a 'webjs' module",
+        .matches = imports_webjs,
+    },
+};
+
+const wli_detector *wasm_lang_inference_detectors(uint32_t abi_version, size_t *count) {
+    if (abi_version != WLI_ABI_VERSION) {
+        return NULL;
+    }
+    *count = sizeof(detectors) / sizeof(detectors[0]);
+    return detectors;
+}
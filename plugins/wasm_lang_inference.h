@@ -0,0 +1,56 @@
+/*
+ * Plugin ABI for wasm-lang-inference, version 1.
+ *
+ * A plugin is a shared library, loaded with `--plugin <path>` when the tool is built with `--features plugins`. It
+ * exports a single entry point, `wasm_lang_inference_detectors`, which returns an array of detectors. These are
+ * consulted in order, after the built-in rules, for modules that would otherwise be classified as unknown - the
+ * first one that matches determines the language, and its id is reported as a `plugin=<id>` hint.
+ *
+ * All strings handed to the plugin are UTF-8 and are NOT NUL-terminated, use the accompanying length. They, and the
+ * module itself, are only valid for the duration of the `matches` call. The strings returned by the plugin must be
+ * NUL-terminated and live as long as the library is loaded (which is the life of the process).
+ */
+#ifndef WASM_LANG_INFERENCE_H
+#define WASM_LANG_INFERENCE_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#define WLI_ABI_VERSION 1
+
+typedef struct {
+    const uint8_t *ptr;
+    size_t len;
+} wli_str;
+
+/* What is known about the module being classified. import_modules[i] / import_names[i] form the i-th import. */
+typedef struct {
+    const wli_str *import_modules;
+    const wli_str *import_names;
+    size_t import_count;
+    const wli_str *export_names;
+    size_t export_count;
+    const wli_str *custom_sections;
+    size_t custom_section_count;
+    /* the size of the module in bytes */
+    size_t size;
+} wli_module;
+
+typedef struct {
+    /* a stable identifier for the heuristic, e.g. "acme.pascal_runtime" */
+    const char *id;
+    /* one of the tool's language names, e.g. "rust", "emscripten" or "go" */
+    const char *language;
+    const char *description;
+    /* required - a plugin with a NULL matches fails to load */
+    bool (*matches)(const wli_module *module);
+} wli_detector;
+
+/*
+ * Called once, when the plugin is loaded, with the ABI version the tool implements. Sets *count and returns the
+ * plugin's detectors, or returns NULL if the plugin doesn't support that version.
+ */
+const wli_detector *wasm_lang_inference_detectors(uint32_t abi_version, size_t *count);
+
+#endif
@@ -0,0 +1,223 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use wasm_lang_inference::{
+    classify, format_hints, min_feature_level, parse_wasm_with, ParseOptions,
+};
+use wasmparser::{ExternalKind, Type, TypeRef};
+
+fn import_kind(ty: &TypeRef) -> &'static str {
+    match ty {
+        TypeRef::Func(_) => "func",
+        TypeRef::Table(_) => "table",
+        TypeRef::Memory(_) => "memory",
+        TypeRef::Global(_) => "global",
+        TypeRef::Tag(_) => "tag",
+    }
+}
+
+fn export_kind(kind: &ExternalKind) -> &'static str {
+    match kind {
+        ExternalKind::Func => "func",
+        ExternalKind::Table => "table",
+        ExternalKind::Memory => "memory",
+        ExternalKind::Global => "global",
+        ExternalKind::Tag => "tag",
+    }
+}
+
+// Prints everything that is known about a single module - the starting point when a module lands in Unknown and you
+// want to work out a new heuristic for it
+pub fn analyze(args: &[String]) -> Result<()> {
+    let path = match args {
+        [path] => path,
+        _ => bail!("usage: wasm-lang-inference analyze <module.wasm>"),
+    };
+    let buf = fs::read(path).with_context(|| format!("failed to read {path}"))?;
+    let module = parse_wasm_with(
+        &buf,
+        &ParseOptions {
+            scan_code: true,
+            deep: true,
+            ..ParseOptions::default()
+        },
+    );
+    if let Some(e) = module.error {
+        bail!("{path}: {e}");
+    }
+    let classification = classify(&module);
+
+    println!("{path}");
+    println!("size: {} bytes", module.size);
+    if module.partial {
+        println!("partial: parsing stopped early at a malformed section");
+    }
+    if module.oversized {
+        println!("oversized: only the first of its imports / exports were kept");
+    }
+    if module.is_stripped() {
+        println!("stripped: no name or producers section, weak import / export patterns are trusted less");
+    }
+
+    if let Some(dylink) = &module.dylink {
+        println!();
+        println!(
+            "dynamically linked, reserving {} bytes of memory (aligned to {}) and {} table entries (aligned to {})",
+            dylink.memory_size,
+            1u64 << dylink.memory_alignment.min(63),
+            dylink.table_size,
+            1u64 << dylink.table_alignment.min(63)
+        );
+        println!("needing ({}):", dylink.needed.len());
+        for needed in &dylink.needed {
+            println!("  {needed}");
+        }
+    }
+
+    if let Some(component) = &module.component {
+        println!();
+        println!("component, importing interfaces:");
+        for interface in &component.imports {
+            println!("  {interface}");
+        }
+        println!("exporting interfaces:");
+        for interface in &component.exports {
+            println!("  {interface}");
+        }
+    }
+
+    println!();
+    println!("imports ({}):", module.imports.len());
+    for import in &module.imports {
+        println!(
+            "  {}.{} ({})",
+            import.module,
+            import.name,
+            import_kind(&import.ty)
+        );
+    }
+
+    println!(
+        "import modules: {}",
+        module
+            .import_modules
+            .iter()
+            .copied()
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    println!();
+    println!("exports ({}):", module.exports.len());
+    for export in &module.exports {
+        println!("  {} ({})", export.name, export_kind(&export.kind));
+    }
+
+    println!();
+    println!("custom sections ({}):", module.custom_sections.len());
+    for name in &module.custom_sections {
+        println!("  {name}");
+    }
+
+    if !module.producers.is_empty() {
+        println!();
+        println!("producers:");
+        for producer in &module.producers {
+            println!(
+                "  {}: {} {}",
+                producer.field, producer.name, producer.version
+            );
+        }
+    }
+
+    if let Some(unit) = &module.dwarf {
+        println!();
+        println!("DWARF {} compilation unit:", unit.version);
+        match unit.language {
+            Some(language) => println!("  language: {language:#x}"),
+            None => println!("  language: none"),
+        }
+        println!("  producer: {}", unit.producer.unwrap_or("none"));
+    }
+
+    println!();
+    match module.names.module {
+        Some(name) => println!("name section: module {name}"),
+        None => println!("name section: no module name"),
+    }
+    for (kind, names) in [
+        ("function", &module.names.functions),
+        ("local", &module.names.locals),
+    ] {
+        let examples: Vec<&str> = names.iter().take(5).copied().collect();
+        let more = if names.len() > examples.len() {
+            ", ..."
+        } else {
+            ""
+        };
+        println!(
+            "  {} {kind} names{}{}{more}",
+            names.len(),
+            if examples.is_empty() { "" } else { ": " },
+            examples.join(", ")
+        );
+    }
+
+    println!();
+    println!("tables ({}):", module.table_count());
+    for table in &module.tables {
+        let element_type = if table.element_type == Type::ExternRef {
+            "externref"
+        } else {
+            "funcref"
+        };
+        match table.maximum {
+            Some(maximum) => println!("  {} - {} entries ({element_type})", table.initial, maximum),
+            None => println!("  {}+ entries ({element_type})", table.initial),
+        }
+    }
+    println!(
+        "imported: memory {}, table {}",
+        if module.imports_memory { "yes" } else { "no" },
+        if module.imports_table { "yes" } else { "no" }
+    );
+    println!(
+        "element segments: {} ({} entries)",
+        module.element_segments, module.element_entries
+    );
+    println!();
+    let features = module.features();
+    if features.is_empty() {
+        println!("features: none (MVP)");
+    } else {
+        println!("features: {}", features.join(", "));
+    }
+    println!("minimum feature level: {}", min_feature_level(&features));
+    if module.uses_gc {
+        println!("i31ref: {}", if module.uses_i31 { "yes" } else { "no" });
+    }
+    println!(
+        "externref: {}",
+        if module.uses_externref { "yes" } else { "no" }
+    );
+
+    println!();
+    println!("language: {:?}", classification.language);
+    match classification.rule {
+        Some(rule) => println!(
+            "evidence: {} - {} (confidence {})",
+            rule.id, rule.description, rule.confidence
+        ),
+        None => println!("evidence: no rule matched"),
+    }
+    if !classification.hints.is_empty() {
+        println!("hints: {}", format_hints(&classification.hints));
+    }
+    if let Some(binding) = classification.js_binding {
+        println!("js binding: {binding}");
+    }
+    if let Some(runnability) = classification.runnability {
+        println!("runnability: {runnability}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use wasm_lang_inference::json::Json;
+use wasm_lang_inference::Language;
+
+// A stored histogram, as a JSON object of language names to counts, e.g. `{"rust": 9, "emscripten": 26}`
+fn read(path: &Path) -> Result<BTreeMap<Language, usize>> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let entries = match text
+        .parse()
+        .with_context(|| format!("failed to parse {}", path.display()))?
+    {
+        Json::Object(entries) => entries,
+        _ => bail!("{}: expected an object of language counts", path.display()),
+    };
+    let mut counts = BTreeMap::new();
+    for (name, count) in entries {
+        let language: Language = name
+            .parse()
+            .with_context(|| format!("{}", path.display()))?;
+        let count = match count {
+            Json::Number(n) if n >= 0.0 && n.fract() == 0.0 => n as usize,
+            _ => bail!(
+                "{}: the count for {name} isn't a whole number",
+                path.display()
+            ),
+        };
+        counts.insert(language, count);
+    }
+    Ok(counts)
+}
+
+fn write<L: Borrow<Language>>(path: &Path, counts: &BTreeMap<L, usize>) -> Result<()> {
+    let json = Json::object(
+        counts
+            .iter()
+            .map(|(language, count)| (language.borrow().name(), (*count).into())),
+    );
+    fs::write(path, format!("{json}\n"))
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+// Compares the counts of a scan to the baseline stored at `path`, failing if any language's count has moved by more
+// than `tolerance` percent of its count in the baseline - so a tolerance of 0, the default, fails on any change. With
+// `update`, the baseline is rewritten from the counts instead, for when the change is intended.
+pub fn check<L: Borrow<Language>>(
+    path: &Path,
+    counts: &BTreeMap<L, usize>,
+    tolerance: f64,
+    update: bool,
+) -> Result<()> {
+    if update {
+        write(path, counts)?;
+        eprintln!("updated the baseline in {}", path.display());
+        return Ok(());
+    }
+
+    let baseline = read(path)?;
+    let current: BTreeMap<Language, usize> = counts
+        .iter()
+        .map(|(language, count)| (*language.borrow(), *count))
+        .collect();
+    let mut changes = vec![];
+    for language in Language::ALL {
+        let expected = *baseline.get(language).unwrap_or(&0);
+        let actual = *current.get(language).unwrap_or(&0);
+        let allowed = expected as f64 * tolerance / 100.0;
+        if (actual as f64 - expected as f64).abs() > allowed {
+            changes.push(format!(
+                "  {}: {expected} -> {actual} ({:+})",
+                language.name(),
+                actual as i64 - expected as i64
+            ));
+        }
+    }
+    if changes.is_empty() {
+        return Ok(());
+    }
+    eprintln!("the counts have changed from the baseline:");
+    for change in &changes {
+        eprintln!("{change}");
+    }
+    Err(anyhow!(
+        "{} languages differ from the baseline in {}, rerun with --update-baseline if this is intended",
+        changes.len(),
+        path.display()
+    ))
+}
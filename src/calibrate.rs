@@ -0,0 +1,141 @@
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use wasm_lang_inference::{classify, parse_wasm, Language, WasmModule, RULES};
+
+// The language a module's producers section says it was written in, for the toolchains that record one: a `language`
+// entry (`Rust`, `C11`, `C_plus_plus_14`, `Swift`, `Go`), or failing that the compiler that processed it. rustc is
+// checked ahead of clang, as Rust modules list clang too when a crate has C dependencies.
+fn producers_language(module: &WasmModule) -> Option<Language> {
+    let is_c = |name: &str| {
+        name == "C"
+            || name.starts_with("C++")
+            || name.starts_with("C_plus_plus")
+            || name
+                .strip_prefix('C')
+                .is_some_and(|version| version.parse::<u32>().is_ok())
+    };
+    let language = |field: &str, is_name: &dyn Fn(&str) -> bool| {
+        module
+            .producers
+            .iter()
+            .any(|p| p.field == field && is_name(p.name))
+    };
+    if language("language", &|name| name == "Rust") {
+        Some(Language::Rust)
+    } else if language("language", &|name| name == "Swift") {
+        Some(Language::Swift)
+    } else if language("language", &|name| name == "Go") {
+        Some(Language::Go)
+    } else if language("language", &is_c) {
+        Some(Language::Emscripten)
+    } else if language("processed-by", &|name| {
+        name == "rustc" || name == "wasm-bindgen"
+    }) {
+        Some(Language::Rust)
+    } else if language("processed-by", &|name| name == "clang") {
+        Some(Language::Emscripten)
+    } else {
+        None
+    }
+}
+
+#[derive(Default)]
+struct Accuracy {
+    matched: usize,
+    correct: usize,
+}
+
+impl Accuracy {
+    fn record(&mut self, correct: bool) {
+        self.matched += 1;
+        if correct {
+            self.correct += 1;
+        }
+    }
+
+    fn percent(&self) -> String {
+        format!("{:.0}%", self.correct as f64 * 100.0 / self.matched as f64)
+    }
+}
+
+// Measures the heuristics without a hand-labelled set: modules with a producers section say what they were built from,
+// so the rules are checked against that, with the producers section hidden from them. Every rule that matches a module
+// is counted, not just the one that decides its language, to show how each would fare on its own - on modules that
+// happen to keep their producers section, which stripped production builds don't, so it is a guide rather than a
+// measure of the corpus as a whole.
+pub fn calibrate(args: &[String]) -> Result<()> {
+    let dir = match args {
+        [] => PathBuf::from("./wasm"),
+        [dir] => PathBuf::from(dir),
+        _ => bail!("usage: wasm-lang-inference --calibrate [directory]"),
+    };
+    let mut paths = vec![];
+    for entry in fs::read_dir(&dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_file() {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut without_producers = 0;
+    let mut overall = Accuracy::default();
+    let mut rules: BTreeMap<usize, Accuracy> = BTreeMap::new();
+    for path in paths {
+        let buf = fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+        if !buf.starts_with(b"\0asm") {
+            continue;
+        }
+        let mut module = parse_wasm(&buf);
+        let truth = match producers_language(&module) {
+            Some(truth) => truth,
+            None => {
+                without_producers += 1;
+                continue;
+            }
+        };
+        module.producers.clear();
+        overall.record(classify(&module).language == truth);
+        for (index, rule) in RULES.iter().enumerate() {
+            if rule.matches(&module) {
+                rules
+                    .entry(index)
+                    .or_default()
+                    .record(rule.language == truth);
+            }
+        }
+    }
+
+    if overall.matched == 0 {
+        bail!(
+            "none of the modules in {} name their language in a producers section",
+            dir.display()
+        );
+    }
+    println!(
+        "calibrated against {} modules with a producers section ({without_producers} without one naming their language were left out)",
+        overall.matched
+    );
+    println!(
+        "{} of them were classified correctly without it ({})",
+        overall.correct,
+        overall.percent()
+    );
+    println!();
+    let width = RULES.iter().map(|rule| rule.id.len()).max().unwrap_or(0);
+    println!("{:width$}  matched  correct  accuracy  confidence", "rule");
+    for (index, accuracy) in &rules {
+        let rule = &RULES[*index];
+        println!(
+            "{:width$}  {:>7}  {:>7}  {:>8}  {:>10}",
+            rule.id,
+            accuracy.matched,
+            accuracy.correct,
+            accuracy.percent(),
+            rule.confidence
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use wasm_lang_inference::Language;
+
+const HEADER: &str = "# wasm-lang-inference checkpoint v1";
+const WRITE_INTERVAL: Duration = Duration::from_secs(10);
+
+// The progress of a long scan - each file classified so far and its language - so that an interrupted scan can be
+// resumed where it left off. Saved as a `language<TAB>path` line per file, rewritten in full every few seconds.
+pub struct Checkpoint {
+    path: PathBuf,
+    done: BTreeMap<PathBuf, Language>,
+    last_write: Instant,
+    // held while a snapshot is being written, so that two of them can't write the temporary file at once
+    writing: Arc<Mutex<()>>,
+}
+
+// A copy of the checkpoint's progress, to be written without holding up the workers that record on the checkpoint
+pub struct Snapshot {
+    path: PathBuf,
+    done: BTreeMap<PathBuf, Language>,
+    writing: Arc<Mutex<()>>,
+}
+
+impl Checkpoint {
+    // A missing file is a fresh start
+    pub fn load(path: &Path) -> Result<Checkpoint> {
+        let mut done = BTreeMap::new();
+        if path.exists() {
+            let contents = fs::read_to_string(path)
+                .with_context(|| format!("failed to read checkpoint {}", path.display()))?;
+            let mut lines = contents.lines();
+            if lines.next() != Some(HEADER) {
+                bail!("{} isn't a checkpoint file", path.display());
+            }
+            for line in lines {
+                let (language, file) = line
+                    .split_once('\t')
+                    .with_context(|| format!("malformed checkpoint line '{line}'"))?;
+                done.insert(PathBuf::from(file), language.parse()?);
+            }
+        }
+        Ok(Checkpoint {
+            path: path.to_path_buf(),
+            done,
+            last_write: Instant::now(),
+            writing: Arc::default(),
+        })
+    }
+
+    pub fn language(&self, file: &Path) -> Option<Language> {
+        self.done.get(file).copied()
+    }
+
+    // Returns a snapshot to write once one is due, which the caller writes after letting go of the checkpoint
+    pub fn record(&mut self, file: &Path, language: Language) -> Option<Snapshot> {
+        self.done.insert(file.to_path_buf(), language);
+        if self.last_write.elapsed() < WRITE_INTERVAL {
+            return None;
+        }
+        self.last_write = Instant::now();
+        Some(self.snapshot())
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            path: self.path.clone(),
+            done: self.done.clone(),
+            writing: Arc::clone(&self.writing),
+        }
+    }
+
+    // the final write, once every worker has finished
+    pub fn write(self) -> Result<()> {
+        Snapshot {
+            path: self.path,
+            done: self.done,
+            writing: self.writing,
+        }
+        .write()
+    }
+}
+
+impl Snapshot {
+    // Written to a temporary file alongside and renamed over the checkpoint, so that being interrupted part way
+    // through writing leaves the previous checkpoint intact
+    pub fn write(&self) -> Result<()> {
+        let _writing = self.writing.lock().unwrap();
+        let mut temp = self.path.clone().into_os_string();
+        temp.push(".tmp");
+        let temp = PathBuf::from(temp);
+        let mut file = fs::File::create(&temp)
+            .with_context(|| format!("failed to write {}", temp.display()))?;
+        let mut contents = format!("{HEADER}\n");
+        for (path, language) in &self.done {
+            contents += &format!("{}\t{}\n", language.name(), path.display());
+        }
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp, &self.path)
+            .with_context(|| format!("failed to write checkpoint {}", self.path.display()))?;
+        Ok(())
+    }
+}
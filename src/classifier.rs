@@ -0,0 +1,101 @@
+use crate::parse::{parse_wasm_reusing, ParseError};
+use crate::{classify, Classification, FuncType, Language, ParseOptions, TableType};
+use std::collections::BTreeMap;
+use std::mem;
+
+// Running totals over every module a Classifier has seen
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Summary {
+    pub counts: BTreeMap<Language, usize>,
+    pub total: usize,
+    // modules that were only partially parsed, see WasmModule::partial
+    pub partial: usize,
+    // modules with a wasm version that can't be read, see WasmModule::error
+    pub unsupported_version: usize,
+    // buffers that don't start with the wasm magic number at all
+    pub not_wasm: usize,
+}
+
+impl Summary {
+    pub fn unclassified_percent(&self) -> f32 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        *self.counts.get(&Language::Unknown).unwrap_or(&0) as f32 * 100.0 / self.total as f32
+    }
+}
+
+// Classifies modules one at a time as they arrive, e.g. from a live crawl, keeping a histogram of the results so that
+// nothing needs to be re-scanned to report on the whole feed. Share one between threads behind a Mutex, or give each
+// worker its own and combine them with `merge`.
+#[derive(Default)]
+pub struct Classifier {
+    options: ParseOptions,
+    summary: Summary,
+    // emptied after each module, so that the next is parsed into the capacity it left behind
+    types: Vec<FuncType>,
+    tables: Vec<TableType>,
+}
+
+impl Classifier {
+    pub fn new(options: ParseOptions) -> Classifier {
+        Classifier {
+            options,
+            ..Classifier::default()
+        }
+    }
+
+    pub fn classify(&mut self, buf: &[u8]) -> Classification {
+        let mut module = parse_wasm_reusing(
+            buf,
+            &self.options,
+            mem::take(&mut self.types),
+            mem::take(&mut self.tables),
+        );
+        let classification = classify(&module);
+        *self
+            .summary
+            .counts
+            .entry(classification.language)
+            .or_insert(0) += 1;
+        self.summary.total += 1;
+        if module.partial {
+            self.summary.partial += 1;
+        }
+        match module.error {
+            Some(ParseError::UnsupportedVersion(_)) => self.summary.unsupported_version += 1,
+            Some(ParseError::NotWasm) => self.summary.not_wasm += 1,
+            None => {}
+        }
+        self.types = mem::take(&mut module.types);
+        self.types.clear();
+        self.tables = mem::take(&mut module.tables);
+        self.tables.clear();
+        classification
+    }
+
+    // Classifies each buffer as the iterator is advanced, adding it to the summary as `classify` does. The type and
+    // table lists are handed from one module to the next, so once they have grown to fit the largest module seen they
+    // aren't reallocated; the imports, exports, names and so on borrow from each module's bytes, so are allocated
+    // afresh for every module, as are the hints of each classification.
+    pub fn classify_many<'a>(
+        &'a mut self,
+        modules: impl Iterator<Item = &'a [u8]> + 'a,
+    ) -> impl Iterator<Item = Classification> + 'a {
+        modules.map(move |buf| self.classify(buf))
+    }
+
+    pub fn summary(&self) -> Summary {
+        self.summary.clone()
+    }
+
+    pub fn merge(&mut self, other: &Summary) {
+        for (language, count) in &other.counts {
+            *self.summary.counts.entry(*language).or_insert(0) += count;
+        }
+        self.summary.total += other.total;
+        self.summary.partial += other.partial;
+        self.summary.unsupported_version += other.unsupported_version;
+        self.summary.not_wasm += other.not_wasm;
+    }
+}
@@ -0,0 +1,133 @@
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::env;
+use std::io::{self, IsTerminal};
+use wasm_lang_inference::Language;
+
+// Whether to colour the language names in the text output. Only the text format is ever coloured - the machine-readable
+// formats, and the table, whose columns are aligned by character count, never are.
+#[derive(PartialEq, Clone, Copy)]
+pub enum ColorChoice {
+    // when stdout is a terminal, and NO_COLOR (https://no-color.org) isn't set
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                    && io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+// the SGR parameters for each language, chosen to be told apart on both dark and light backgrounds
+fn sgr(language: Language) -> &'static str {
+    match language {
+        // orange, from the 256 colour palette
+        Language::Rust => "38;5;208",
+        Language::Emscripten => "34",
+        Language::AssemblyScript => "35",
+        Language::Blazor => "38;5;99",
+        Language::Go => "36",
+        Language::HandWritten => "33",
+        Language::Scheme => "31",
+        Language::Julia => "32",
+        Language::Swift => "38;5;203",
+        Language::Dart => "38;5;39",
+        Language::Java => "38;5;172",
+        Language::Elm => "38;5;74",
+        Language::Gren => "38;5;71",
+        // dimmed, as there's nothing to see
+        Language::Unknown
+        | Language::Empty
+        | Language::ObjectFile
+        | Language::DebugInfo
+        | Language::Coredump => "2",
+    }
+}
+
+// the language as it is printed in the text output, e.g. `Rust`
+pub fn language(language: Language, color: bool) -> String {
+    if color {
+        format!("\x1b[{}m{language:?}\x1b[0m", sgr(language))
+    } else {
+        format!("{language:?}")
+    }
+}
+
+// The summary histogram, e.g. `{Rust: 2, Go: 1}` - identical to the counts' Debug output when uncoloured
+pub fn histogram<L: Borrow<Language>>(counts: &BTreeMap<L, usize>, color: bool) -> String {
+    let entries: Vec<String> = counts
+        .iter()
+        .map(|(l, count)| format!("{}: {count}", language(*l.borrow(), color)))
+        .collect();
+    format!("{{{}}}", entries.join(", "))
+}
+
+// The width to fit the bar chart to. There's no portable way to ask the terminal without a dependency, but shells set
+// COLUMNS, and 80 columns fits everything else.
+pub fn terminal_width() -> usize {
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .filter(|&columns| columns > 0)
+        .unwrap_or(80)
+}
+
+// The summary histogram as a bar chart, largest first, with the bars scaled so the longest fills the line, e.g.
+// `Rust  ████████████  42 (38%)`
+pub fn bars<L: Borrow<Language>>(counts: &BTreeMap<L, usize>, color: bool, width: usize) -> String {
+    let mut counts: Vec<(Language, usize)> = counts
+        .iter()
+        .map(|(l, count)| (*l.borrow(), *count))
+        .collect();
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    let total: usize = counts.iter().map(|(_, count)| count).sum();
+    let longest = counts.first().map_or(0, |(_, count)| *count);
+    let name_width = counts
+        .iter()
+        .map(|(l, _)| format!("{l:?}").len())
+        .max()
+        .unwrap_or(0);
+    let count_width = longest.to_string().len();
+    // the name, the count and its percentage, and the gaps between them, leaving at least a short bar on narrow
+    // terminals rather than none
+    let bar_width = width
+        .saturating_sub(name_width + count_width + " (100%)".len() + 4)
+        .max(10);
+
+    let lines: Vec<String> = counts
+        .iter()
+        .map(|(l, count)| {
+            // every language that was seen gets a block, however few modules it has
+            let blocks = (count * bar_width + longest / 2)
+                .checked_div(longest)
+                .unwrap_or(0)
+                .max(1);
+            let bar = "█".repeat(blocks);
+            let bar = if color {
+                format!("\x1b[{}m{bar}\x1b[0m", sgr(*l))
+            } else {
+                bar
+            };
+            // padded by hand, as the colour codes would throw the format's padding off
+            let name_padding = name_width - format!("{l:?}").len();
+            let bar_padding = bar_width.saturating_sub(blocks);
+            let percent = *count as f64 * 100.0 / total as f64;
+            format!(
+                "{}{:name_padding$}  {bar}{:bar_padding$}  {count:>count_width$} ({percent:.0}%)",
+                language(*l, color),
+                "",
+                ""
+            )
+        })
+        .collect();
+    lines.join("\n")
+}
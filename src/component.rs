@@ -0,0 +1,139 @@
+use wasmparser::{BinaryReader, Result};
+
+// The interfaces a component imports and exports, named by their WIT ids, e.g. `wasi:cli/environment@0.2.0`. These
+// are the world the component targets, which is what a plugin host needs to know, whereas the language it was written
+// in is only visible in the core modules inside it.
+#[derive(Default)]
+pub struct ComponentInterfaces<'a> {
+    pub imports: Vec<&'a str>,
+    pub exports: Vec<&'a str>,
+}
+
+// the version and layer fields that follow the magic number in a component, rather than a core module
+const COMPONENT_HEADER: [u8; 4] = [0x0d, 0x00, 0x01, 0x00];
+
+pub fn is_component(buf: &[u8]) -> bool {
+    buf.get(4..8) == Some(&COMPONENT_HEADER)
+}
+
+const CORE_MODULE_SECTION: u8 = 1;
+const COMPONENT_SECTION: u8 = 4;
+const IMPORT_SECTION: u8 = 10;
+const EXPORT_SECTION: u8 = 11;
+// the instance sort, and the matching extern descriptor
+const INSTANCE: u8 = 0x05;
+
+// The wasmparser version in use only knows an early draft of the component format, so this reads just enough of the
+// current one by hand: the top-level import and export sections, keeping the names of the instances, i.e. interfaces,
+// and skipping everything else, including any nested core modules and components. Functions, values and types imported
+// or exported individually are named in plain kebab-case rather than as interfaces, so aren't kept.
+pub(crate) fn read_component<'a>(
+    buf: &'a [u8],
+    interfaces: &mut ComponentInterfaces<'a>,
+) -> Result<()> {
+    let mut reader = BinaryReader::new(&buf[8..]);
+    while !reader.eof() {
+        let id = reader.read_u8()?;
+        let len = reader.read_var_u32()? as usize;
+        let mut section = BinaryReader::new(reader.read_bytes(len)?);
+        match id {
+            IMPORT_SECTION => {
+                for _ in 0..section.read_var_u32()? {
+                    let name = read_extern_name(&mut section)?;
+                    if read_extern_desc(&mut section)? == INSTANCE {
+                        interfaces.imports.push(name);
+                    }
+                }
+            }
+            EXPORT_SECTION => {
+                for _ in 0..section.read_var_u32()? {
+                    let name = read_extern_name(&mut section)?;
+                    let sort = read_sort(&mut section)?;
+                    section.read_var_u32()?;
+                    // optionally followed by the type the export is ascribed
+                    if section.read_u8()? == 1 {
+                        read_extern_desc(&mut section)?;
+                    }
+                    if sort == INSTANCE {
+                        interfaces.exports.push(name);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+// The core modules embedded in a component, in the order they appear in it, with those of nested components (e.g. from
+// composing several with wac) in place of the component they're in. Each is a complete module in its own right. A
+// component that can't be read in full gives the modules found up to that point.
+pub fn core_modules(buf: &[u8]) -> Vec<&[u8]> {
+    let mut modules = vec![];
+    if is_component(buf) {
+        let _ = read_core_modules(buf, &mut modules);
+    }
+    modules
+}
+
+fn read_core_modules<'a>(buf: &'a [u8], modules: &mut Vec<&'a [u8]>) -> Result<()> {
+    let mut reader = BinaryReader::new(&buf[8..]);
+    while !reader.eof() {
+        let id = reader.read_u8()?;
+        let len = reader.read_var_u32()? as usize;
+        let section = reader.read_bytes(len)?;
+        match id {
+            CORE_MODULE_SECTION => modules.push(section),
+            COMPONENT_SECTION if is_component(section) => read_core_modules(section, modules)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn read_extern_name<'a>(reader: &mut BinaryReader<'a>) -> Result<&'a str> {
+    // 0x00 is a plain name, and 0x01 an interface name in the encodings emitted before the two were merged
+    reader.read_u8()?;
+    reader.read_string()
+}
+
+fn read_sort(reader: &mut BinaryReader) -> Result<u8> {
+    let sort = reader.read_u8()?;
+    // core sorts are followed by the kind of core item
+    if sort == 0x00 {
+        reader.read_u8()?;
+    }
+    Ok(sort)
+}
+
+// returns the kind of item described, the same byte as its sort
+fn read_extern_desc(reader: &mut BinaryReader) -> Result<u8> {
+    let kind = reader.read_u8()?;
+    match kind {
+        // a core module, `0x11` then its type index
+        0x00 => {
+            reader.read_u8()?;
+            reader.read_var_u32()?;
+        }
+        // a value, bound to another value or given a value type
+        0x02 => match reader.read_u8()? {
+            0x00 => {
+                reader.read_var_u32()?;
+            }
+            _ => {
+                reader.read_var_s33()?;
+            }
+        },
+        // a type, either equal to another type or fresh resource
+        0x03 => {
+            if reader.read_u8()? == 0x00 {
+                reader.read_var_u32()?;
+            }
+        }
+        // functions, components and instances, by type index
+        _ => {
+            reader.read_var_u32()?;
+        }
+    }
+    Ok(kind)
+}
@@ -0,0 +1,1870 @@
+use crate::hash::hex;
+use crate::{parse_wasm, Hints, Language, WasmModule};
+use wasmparser::{ExternalKind, Import, Type, TypeRef};
+
+fn is_emscripten(module: &WasmModule) -> bool {
+    module.any_imports_match(|i| i.name.to_string().contains("emscripten"))
+}
+
+fn is_likely_emscripten(module: &WasmModule) -> bool {
+    // Many of the wasm modules have been compressed with this very distinctive pattern. From looking at a number of wasm modules
+    // and inspecting their contents, or the page that hosts them, it seems quite likely this is Emscripten. For example:
+    //
+    // https://tweet2doom.github.io/t2d-explorer.wasm
+    //   => https://github.com/tweet2doom/tweet2doom.github.io - strong evidence of Emscripten
+    //
+    // https://graphonline.ru/script/Graphoffline.Emscripten.wasm - the clue is in the filename!
+    //
+    // https://wsr-starfinder.com/js/stellarium-web-engine.06229ae9.wasm
+    //  => https://github.com/Stellarium/stellarium-web-engine - code makes reference to using Emscripten
+    (module.any_imports_match(|i| i.module == "a" && i.name == "a")
+        && module.any_imports_match(|i| i.module == "a" && i.name == "b"))
+
+    // another distinctive pattern, again, evidence suggests Emscripten
+    // https://tx.me/
+    // => https://github.com/Samsung/rlottie/blob/master/src/wasm/rlottiewasm.cpp - this is a cool project ;-)
+    //
+    // https://demo.harmonicvision.com - Emscripten mentioned in the page source
+    //
+    // https://webcamera.io - uses FFMpeg, which is an Emscripten project
+    || (module.any_imports_match(|i| i.module == "env" && i.name == "a")
+        && module.any_imports_match(|i| i.module == "env" && i.name == "b"))
+
+    // exporting malloc is a C giveaway!
+    || module.any_exports_match(|e| e.name == "malloc")
+}
+
+fn manages_emscripten_stack(module: &WasmModule) -> bool {
+    // Emscripten's JS glue calls into the module to save, restore and allocate on the C stack, around calls with
+    // stack-allocated arguments - `stackSave` / `stackRestore` / `stackAlloc`, or in newer versions
+    // `emscripten_stack_*` and `_emscripten_stack_restore`. The glue looks them up by name, so they survive
+    // minification, unlike the import names that emscripten.likely has to go on.
+    let is_stack_name = |name: &str| {
+        matches!(name, "stackSave" | "stackRestore" | "stackAlloc")
+            || name.starts_with("emscripten_stack_")
+            || name.starts_with("_emscripten_stack_")
+    };
+    module.any_exports_match(|e| is_stack_name(e.name))
+        || module.any_imports_match(|i| is_stack_name(i.name))
+}
+
+fn is_invoke_thunk(name: &str) -> bool {
+    // `invoke_` followed by the signature of the function it calls, in Emscripten's letters: `v`oid, `i`32, `j` (i64),
+    // `f`32, `d` (f64), `p`ointer and `e` (externref)
+    name.strip_prefix("invoke_").is_some_and(|signature| {
+        !signature.is_empty() && signature.chars().all(|c| "vijfdpe".contains(c))
+    })
+}
+
+fn uses_js_exceptions(module: &WasmModule) -> bool {
+    // Emscripten's default, JS-based exception handling (and its setjmp / longjmp) can't unwind the wasm stack itself,
+    // so every call that might throw goes out through an `invoke_vi` / `invoke_iii` / ... thunk in the JS glue, which
+    // calls back in inside a JS `try`. The glue implements them, so they are imported from `env` by name - only the
+    // import minification that emscripten.likely goes on hides them. Builds with `-fwasm-exceptions` use the exception
+    // handling proposal's tags instead, and have none.
+    module.any_imports_match(|i| i.module == "env" && is_invoke_thunk(i.name))
+}
+
+fn emscripten_exception_handling(module: &WasmModule) -> Option<&'static str> {
+    if uses_js_exceptions(module) {
+        Some("js")
+    } else if module.uses_exception_tags() {
+        Some("wasm")
+    } else {
+        None
+    }
+}
+
+fn exposes_c_api_to_js(module: &WasmModule) -> bool {
+    // Modules built as a C library for JS to call (`-sEXPORTED_FUNCTIONS` with
+    // `-sEXPORTED_RUNTIME_METHODS=ccall,cwrap`) export the API itself under its C names, e.g. `sqlite3_open`, alongside
+    // the helpers ccall needs to pass string and array arguments - `stackAlloc` (`_emscripten_stack_alloc` in newer
+    // versions) to copy them onto the C stack, bracketed by `stackSave` / `stackRestore`. Standalone programs only
+    // export `main` and the runtime's own functions.
+    let is_runtime_export = |name: &str| {
+        name.starts_with('_')
+            || name.len() <= 2
+            || name.starts_with("stack")
+            || name.starts_with("emscripten_")
+            || name.starts_with("asyncify_")
+            || name.starts_with("dynCall_")
+            || matches!(
+                name,
+                "memory" | "main" | "malloc" | "free" | "fflush" | "setThrew" | "strerror"
+            )
+    };
+    module.any_exports_match(|e| e.name == "stackAlloc" || e.name == "_emscripten_stack_alloc")
+        && module.any_exports_match(|e| {
+            matches!(e.kind, ExternalKind::Func) && !is_runtime_export(e.name)
+        })
+}
+
+fn has_wasi_libc_main(module: &WasmModule) -> bool {
+    // clang compiles C / C++'s `int main(int, char **)` to `__main_argc_argv` on wasm, and a `main` without parameters
+    // to `__original_main`, so that wasi-libc's `_start` can tell which to call, through its `__main_void` shim. Only
+    // LLVM-based toolchains use these names, and they survive in the name section of all but stripped modules.
+    let is_shim = |name: &str| matches!(name, "__main_argc_argv" | "__original_main");
+    module.names.functions.iter().any(|name| is_shim(name))
+        || module.any_exports_match(|e| is_shim(e.name))
+}
+
+fn is_clang_wasi_program(module: &WasmModule) -> bool {
+    // Rust and SwiftWasm programs link wasi-libc's startup code too, with their own main compiled to the same entry
+    // points, so they are only C / C++ when there's no sign of either language's runtime
+    has_wasi_libc_main(module) && !is_rust_toolchain(module) && !has_swift_runtime(module)
+}
+
+// DW_LANG_* constants, see https://dwarfstd.org/languages.html
+const DW_LANG_C: &[u16] = &[0x01, 0x02, 0x0c, 0x1d, 0x2c];
+const DW_LANG_CPP: &[u16] = &[0x04, 0x19, 0x1a, 0x21, 0x2a, 0x2b];
+const DW_LANG_RUST: u16 = 0x1c;
+const DW_LANG_SWIFT: u16 = 0x1e;
+
+// The source language named by the first compilation unit of the DWARF debug info, along with the source_language
+// hint for it, as Language doesn't tell C and C++ apart. The compiler writes the attribute itself, so nothing is more
+// certain, but it's only there in debug builds, and only read with ParseOptions::deep.
+fn dwarf_source_language(module: &WasmModule) -> Option<(Language, &'static str)> {
+    match module.dwarf.as_ref()?.language? {
+        DW_LANG_RUST => Some((Language::Rust, "rust")),
+        DW_LANG_SWIFT => Some((Language::Swift, "swift")),
+        language if DW_LANG_C.contains(&language) => Some((Language::Emscripten, "c")),
+        language if DW_LANG_CPP.contains(&language) => Some((Language::Emscripten, "cpp")),
+        _ => None,
+    }
+}
+
+fn has_dwarf_language(module: &WasmModule, language: Language) -> bool {
+    dwarf_source_language(module).is_some_and(|(l, _)| l == language)
+}
+
+fn is_rust_dwarf(module: &WasmModule) -> bool {
+    has_dwarf_language(module, Language::Rust)
+}
+
+fn is_swift_dwarf(module: &WasmModule) -> bool {
+    has_dwarf_language(module, Language::Swift)
+}
+
+fn is_clang_dwarf(module: &WasmModule) -> bool {
+    has_dwarf_language(module, Language::Emscripten)
+}
+
+fn is_emscripten_side_module(module: &WasmModule) -> bool {
+    // Side modules (-sSIDE_MODULE) are position independent, so that the main module can load them anywhere in its
+    // memory. They import the base addresses they have been given (`env.__memory_base`, `env.__table_base`), and
+    // wasm-ld adds an `__wasm_apply_data_relocs` export that the dynamic linker calls to patch up their data, and a
+    // `dylink.0` section describing what they need from it. They share the main module's memory and function table
+    // too, importing both from `env`. Statically linked modules import their memory at most (-sIMPORTED_MEMORY), not
+    // the table, so it is only the -sMAIN_MODULE that they are loaded into which looks the same.
+    let shares_main_module = module.imports_memory
+        && module.imports_table
+        && module.any_imports_match(|i| i.module == "env" && i.name == "memory")
+        && module.any_imports_match(|i| i.module == "env" && i.name == "__indirect_function_table");
+    module.any_imports_match(|i| i.module == "env" && i.name == "__memory_base")
+        || module.any_exports_match(|e| e.name == "__wasm_apply_data_relocs")
+        || module.dylink.is_some()
+        || shares_main_module
+}
+
+fn exports_static_constructors(module: &WasmModule) -> bool {
+    // wasm-ld synthesises `__wasm_call_ctors` to run C++ static constructors and C `__attribute__((constructor))`
+    // functions. Emscripten's JS calls it on startup, as do hosts of library-style (`--no-entry`) builds, so it is
+    // exported from most LLVM-compiled C / C++ modules that don't have a `_start` to call it for them. Rust links with
+    // wasm-ld too, but wasm-bindgen modules are caught well before this rule.
+    module.any_exports_match(|e| e.name == "__wasm_call_ctors")
+}
+
+fn is_emscripten_pthread_worker(module: &WasmModule) -> bool {
+    // Emscripten builds with -pthread run the module on web workers as well as the main thread. The worker variants
+    // import the shared memory created by the main thread and only export the thread entry points, there's no main.
+    module.imports_shared_memory()
+        && !module.any_exports_match(|e| {
+            e.name == "main"
+                || e.name == "_main"
+                || e.name == "__main_argc_argv"
+                || e.name == "_start"
+        })
+}
+
+fn emscripten_output_mode(module: &WasmModule) -> Option<&'static str> {
+    // MODULARIZE / EXPORT_ES6 only change the generated JS, the wasm itself is identical, so the output mode can only
+    // be inferred from how the exports are named:
+    //  - "classic": the fastcomp-era `Module._main` style, where exports keep the leading underscore of the C symbol
+    //    (`_main`, `_malloc`, `_free`)
+    //  - "minified": production builds that minify import / export names, so that most exports are one or two
+    //    letters (`a`, `b`, ... `Ab`), as seen in modularised builds bundled by webpack et al.
+    // Modern unminified builds export the plain C names in either mode, so no hint is given for those.
+    let functions: Vec<&str> = module
+        .exports
+        .iter()
+        .filter(|e| matches!(e.kind, ExternalKind::Func))
+        .map(|e| e.name)
+        .collect();
+    if functions.is_empty() {
+        return None;
+    }
+
+    if functions
+        .iter()
+        .any(|name| ["_main", "_malloc", "_free"].contains(name))
+    {
+        Some("classic")
+    } else if functions.iter().filter(|name| name.len() <= 2).count() * 2 > functions.len() {
+        Some("minified")
+    } else {
+        None
+    }
+}
+
+fn is_rust(module: &WasmModule) -> bool {
+    module.any_imports_match(|i| {
+        i.name.to_string().contains("wbindgen")
+            || i.name.to_string().contains("wbg")
+            || i.module == "wbg"
+            || i.module == "wbindgen"
+    }) || module.any_exports_match(|e| e.name.to_string().contains("wbindgen"))
+}
+
+fn is_rust_toolchain(module: &WasmModule) -> bool {
+    // Rust modules that don't use wasm-bindgen, e.g. WASI programs, have no glue imports to go on. rustc records itself
+    // in the producers section, which survives unless the module is stripped, as do the symbols of Rust's runtime in
+    // the export or name sections (`__rust_alloc`, `rust_begin_unwind`)
+    let is_rust_symbol = |name: &str| name.starts_with("__rust_") || name == "rust_begin_unwind";
+    module.producers.iter().any(|p| {
+        (p.field == "language" && p.name == "Rust")
+            || (p.field == "processed-by" && p.name == "rustc")
+    }) || module.any_exports_match(|e| is_rust_symbol(e.name))
+        || module
+            .names
+            .functions
+            .iter()
+            .any(|name| is_rust_symbol(name))
+}
+
+fn rust_target(module: &WasmModule) -> &'static str {
+    // The wasm target triple, going by the WASI version imported. Everything else is the web target, whether it uses
+    // wasm-bindgen or raw exports. wasm32-wasip1 was called wasm32-wasi until Rust 1.78.
+    match wasi_version(module) {
+        Some("preview2") => "wasm32-wasip2",
+        Some(_) => "wasm32-wasip1",
+        None => "wasm32-unknown-unknown",
+    }
+}
+
+fn bindgen_role(module: &WasmModule) -> Option<&'static str> {
+    // A wasm-bindgen app can be split over several modules, e.g. a worker alongside the main module, which would count
+    // one Rust app several times over. The main module is the one the JS glue drives, so it exports the bindgen
+    // runtime (`__wbindgen_malloc`, `__wbindgen_start`, ...). Auxiliary modules export none of it and instead import
+    // most of their functions from the main module, rather than from `wbg` or the host.
+    if module.any_exports_match(|e| e.name.starts_with("__wbindgen_")) {
+        return Some("primary");
+    }
+    // the JS glue, as named by any wasm-bindgen version (see bindgen_abi), or the host
+    let is_host = |name: &str| {
+        matches!(
+            name,
+            "wbg" | "wbindgen" | "env" | "__wbindgen_placeholder__"
+        ) || name.ends_with(".js")
+            || name.starts_with("wasi")
+    };
+    let from_other_modules = module
+        .imports
+        .iter()
+        .filter(|i| matches!(i.ty, TypeRef::Func(_)) && !is_host(i.module))
+        .count();
+    (from_other_modules * 2 > module.imported_functions()).then_some("auxiliary")
+}
+
+fn rust_panic_strategy(module: &WasmModule) -> &'static str {
+    // On wasm, unwinding is only possible with the exception handling proposal, so `-C panic=unwind` builds throw and
+    // catch panics as wasm exceptions, defining (or importing) LLVM's `__cpp_exception` tag, and link in the unwinder,
+    // `_Unwind_RaiseException` and the `rust_eh_personality` routine, whose names survive in the name section. Tags
+    // live in a section of their own, which stripping doesn't remove, so without any the panic strategy is the
+    // default, `abort`, which traps on an `unreachable` instead.
+    let is_unwind_symbol =
+        |name: &str| name == "_Unwind_RaiseException" || name == "rust_eh_personality";
+    if module.uses_exception_tags()
+        || module.any_exports_match(|e| is_unwind_symbol(e.name))
+        || module
+            .names
+            .functions
+            .iter()
+            .any(|name| is_unwind_symbol(name))
+    {
+        "unwind"
+    } else {
+        "abort"
+    }
+}
+
+fn bindgen_abi(module: &WasmModule) -> Option<&'static str> {
+    // Where wasm-bindgen points the imports of the JS glue has changed over its history, which dates the output:
+    //  - "unprocessed": rustc's raw output, importing from `__wbindgen_placeholder__`, which the wasm-bindgen CLI would
+    //    normally rewrite - someone shipped the module without running it
+    //  - "js-module": older releases import from the generated JS file by name (`./hello_bg.js`)
+    //  - "wbg": current releases import everything from a `wbg` module, whatever the target
+    let modules = |f: fn(&str) -> bool| module.any_imports_match(|i| f(i.module));
+    if modules(|m| m == "__wbindgen_placeholder__") {
+        Some("unprocessed")
+    } else if modules(|m| m == "wbg") {
+        Some("wbg")
+    } else if modules(|m| m.ends_with(".js")) {
+        Some("js-module")
+    } else {
+        None
+    }
+}
+
+fn is_blazor(module: &WasmModule) -> bool {
+    module.any_imports_match(|i| i.name.to_string().contains("blazor"))
+}
+
+fn is_blazor_fsharp(module: &WasmModule) -> bool {
+    // Blazor apps are mostly C#, but F# apps (e.g. https://fsbolero.io) can be told apart by the assembly names that
+    // find their way into the data section - every F# app depends on FSharp.Core.
+    module.data_contains(b"FSharp.Core")
+}
+
+fn blazor_globalization(module: &WasmModule) -> Option<&'static str> {
+    // .NET links ICU into the runtime for culture-aware formatting and collation, unless the app is relinked with
+    // `InvariantGlobalization`. ICU's code names its data package (`icudt72l`), and its functions keep their versioned
+    // names (`ucol_open_72`) where the name section survives. Without any of these it is only invariant if the module
+    // is the runtime itself, which carries the name of the core library it loads, rather than something smaller that
+    // just imports from Blazor. Best-effort, as a runtime fetched without its data section can't be told either way.
+    let links_icu = module.data_contains(b"icudt")
+        || module
+            .names
+            .functions
+            .iter()
+            .any(|name| name.starts_with("ucol_") || name.starts_with("ubrk_"));
+    if links_icu {
+        Some("icu")
+    } else if module.data_contains(b"System.Private.CoreLib") {
+        Some("invariant")
+    } else {
+        None
+    }
+}
+
+// Go's bridge to its wasm_exec.js glue - `runtime.*` and `syscall/js.*` functions, plus `debug` - imported from `go`,
+// or from `gojs` since Go 1.21. A module merely named `go` by some other toolchain won't import these names.
+fn imports_go_bridge(module: &WasmModule, bridge: &str) -> bool {
+    module.any_imports_match(|i| {
+        i.module == bridge
+            && (i.name.starts_with("runtime.")
+                || i.name.starts_with("syscall/js.")
+                || i.name == "debug")
+    })
+}
+
+fn is_go(module: &WasmModule) -> bool {
+    imports_go_bridge(module, "go") || imports_go_bridge(module, "gojs")
+}
+
+fn has_go_runtime(module: &WasmModule) -> bool {
+    // Go programs built for WASI (GOOS=wasip1, since Go 1.21) have no JS bridge to import, only the same WASI imports
+    // as any other language. Both Go's linker and TinyGo name the functions after their Go symbols, which survive in
+    // the name section unless stripped (`-ldflags=-s`): Go's entry point is `_rt0_wasm_wasip1`, and every program has
+    // a `main.main` alongside the `runtime.*` functions.
+    let names = &module.names.functions;
+    wasi_version(module).is_some()
+        && (names.contains(&"_rt0_wasm_wasip1")
+            || names.contains(&"main.main")
+                && names.iter().any(|name| name.starts_with("runtime.")))
+}
+
+fn is_tinygo(module: &WasmModule) -> bool {
+    // TinyGo has its own wasm_exec.js, which shares Go's syscall/js bridge but keeps time with `runtime.ticks` /
+    // `runtime.sleepTicks` in place of the Go runtime's nanotime and timer imports. Its WASI modules have no bridge,
+    // but keep the `tinygo_*` functions of its goroutine scheduler, which switches stacks with asyncify.
+    module.any_imports_match(|i| {
+        (i.module == "go" || i.module == "gojs")
+            && (i.name == "runtime.ticks" || i.name == "runtime.sleepTicks")
+    }) || module
+        .names
+        .functions
+        .iter()
+        .any(|name| name.starts_with("tinygo_"))
+}
+
+fn go_target(module: &WasmModule) -> Option<&'static str> {
+    // The GOOS/GOARCH pair, for Go and TinyGo alike: js/wasm modules import the JS bridge (and TinyGo's WASI's
+    // `fd_write` as well, for printing), whereas wasip1/wasm modules only import WASI
+    if is_go(module) {
+        Some("js/wasm")
+    } else if wasi_version(module).is_some() {
+        Some("wasip1/wasm")
+    } else {
+        None
+    }
+}
+
+fn go_era(module: &WasmModule) -> Option<&'static str> {
+    // The functions the Go runtime imports from wasm_exec.js have been renamed over the releases, which dates a module
+    // to a range of them:
+    //  - 1.11 scheduled its timers with `runtime.scheduleCallback`, which 1.12 renamed `runtime.scheduleTimeoutEvent`
+    //  - 1.12 to 1.15 import `runtime.nanotime`, which 1.16 renamed `runtime.nanotime1`
+    //  - 1.16 alone imports `runtime.walltime1`, which 1.17 renamed back to `runtime.walltime`
+    //  - 1.21 moved the bridge from the `go` import module to `gojs`, alongside the new wasip1 port, so wasip1 modules
+    //    are 1.21+ too
+    // TinyGo's releases don't follow Go's, so its modules aren't dated.
+    let go = |name: &str| module.any_imports_match(|i| i.module == "go" && i.name == name);
+    if is_tinygo(module) {
+        None
+    } else if imports_go_bridge(module, "gojs") || !is_go(module) && has_go_runtime(module) {
+        Some("1.21+")
+    } else if go("runtime.scheduleCallback") {
+        Some("1.11")
+    } else if go("runtime.walltime1") {
+        Some("1.16")
+    } else if go("runtime.nanotime") {
+        Some("1.12-1.15")
+    } else if go("runtime.nanotime1") {
+        Some("1.17-1.20")
+    } else {
+        None
+    }
+}
+
+fn is_assemblyscript(module: &WasmModule) -> bool {
+    // OK, so this one is *very* hacky! The hyphenate lib (https://github.com/mnater/Hyphenopoly) is found on a number of
+    // websites. It is written in AssemblyScript, and has a variety of different bundles. They all export the function
+    // 'hyphenate'.
+    module.any_exports_match(|e| e.name == "hyphenate")
+}
+
+fn imports_assemblyscript_abort(module: &WasmModule) -> bool {
+    // Plenty of toolchains import an `env.abort`, but AssemblyScript's is called with the message, file name, line and
+    // column of the failed assertion - four i32 parameters, no results - whereas C's abort takes nothing at all
+    module.any_imports_match(|i| {
+        i.module == "env"
+            && i.name == "abort"
+            && module.import_signature(i).is_some_and(|ty| {
+                ty.params.len() == 4
+                    && ty.params.iter().all(|t| *t == Type::I32)
+                    && ty.returns.is_empty()
+            })
+    })
+}
+
+fn exports_assemblyscript_arguments_glue(module: &WasmModule) -> bool {
+    // An exported function with optional parameters can't tell how many arguments JS passed it, so AssemblyScript
+    // exports `__setArgumentsLength` (`__setargc` before 0.10) for the caller to say, which the JS bindings that asc
+    // generates with `--bindings` do before each such call. No other toolchain has anything by either name.
+    module.any_exports_match(|e| e.name == "__setArgumentsLength" || e.name == "__setargc")
+}
+
+fn assemblyscript_runtime_variant(module: &WasmModule) -> Option<&'static str> {
+    // With --exportRuntime, the runtime's memory management functions are exported for the JS loader. The minimal and
+    // incremental (default) GCs export the same interface, including `__collect`, so can't be told apart - whereas
+    // the stub runtime never frees anything and has no collector, and modules from before 0.18 use the reference
+    // counting `__retain` / `__release` runtime.
+    let exports = |name| module.any_exports_match(|e| e.name == name);
+    if exports("__retain") && exports("__release") {
+        Some("refcount")
+    } else if exports("__new") && exports("__collect") {
+        Some("incremental")
+    } else if exports("__new") {
+        Some("stub")
+    } else {
+        None
+    }
+}
+
+fn is_scheme(module: &WasmModule) -> bool {
+    // Best-effort! Schism and Guile's Hoot both compile Scheme to wasm, with the runtime support provided by JS via
+    // imports from an `rt` module. The import names tend to keep their Scheme spelling (`string->utf8`, `eof-object?`,
+    // `%debug`), and as Scheme relies on proper tail calls and garbage collection, modules that use both proposals
+    // alongside an `rt` runtime are very likely Scheme too. Hoot represents fixnums as i31refs, which settles it for
+    // GC modules when the code hasn't been scanned for tail calls.
+    let is_scheme_name = |name: &str| {
+        name.contains("->") || name.ends_with('?') || name.ends_with('!') || name.starts_with('%')
+    };
+
+    module.any_imports_match(|i| i.module == "rt" && is_scheme_name(i.name))
+        || (module.uses_gc
+            && (module.uses_tail_calls || module.uses_i31)
+            && module.any_imports_match(|i| i.module == "rt"))
+}
+
+fn is_dart(module: &WasmModule) -> bool {
+    // dart2wasm targets wasm GC, with its JS interop imported from a `dart2wasm` module (minified to `_1`, `_2`, ... in
+    // release builds) and the entry point exported as `$invokeMain` for its generated .mjs loader to call
+    module.uses_gc
+        && (module.any_imports_match(|i| i.module == "dart2wasm")
+            || module.any_exports_match(|e| e.name == "$invokeMain"))
+}
+
+fn is_teavm(module: &WasmModule) -> bool {
+    // TeaVM compiles JVM bytecode, so Java as well as Kotlin or Scala, and its JS runtime supplies the module's host
+    // functions from modules of its own: `teavm` (logString, currentTimeMillis, ...), `teavmMath` and, with the newer
+    // GC backend, `teavmJso` for JS interop
+    module.any_imports_match(|i| i.module.starts_with("teavm"))
+}
+
+// JWebAssembly emits the JVM's class names in their internal form
+fn is_jvm_name(name: &str) -> bool {
+    name.contains("java/lang/")
+}
+
+fn imports_jwebassembly(module: &WasmModule) -> bool {
+    // Best-effort! JWebAssembly also compiles JVM bytecode, but keeps the JVM's own names for classes, which it emits
+    // in their internal form (`java/lang/String`) in the name section and on imports. Without the GC proposal, its
+    // object model is polyfilled by JS functions imported from a `NonGC` module.
+    module.any_imports_match(|i| i.module == "NonGC" || is_jvm_name(i.name))
+}
+
+fn has_jvm_function_names(module: &WasmModule) -> bool {
+    // the name section comes after the code, so this needs the whole module, unlike the imports
+    module.names.functions.iter().any(|name| is_jvm_name(name))
+}
+
+fn is_jwebassembly(module: &WasmModule) -> bool {
+    imports_jwebassembly(module) || has_jvm_function_names(module)
+}
+
+fn is_flutter(module: &WasmModule) -> bool {
+    // Flutter's skwasm renderer is Skia compiled to a module of its own, whose functions the Dart module imports from
+    // `skwasm`. With the CanvasKit renderer, Flutter's engine bindings still have its name on them.
+    let is_flutter_name = |name: &str| name.to_ascii_lowercase().contains("flutter");
+    module.any_imports_match(|i| i.module == "skwasm" || is_flutter_name(i.name))
+        || module.any_exports_match(|e| is_flutter_name(e.name))
+}
+
+fn imports_javascript_kit(module: &WasmModule) -> bool {
+    // JavaScriptKit, SwiftWasm's bridge to the browser, imports its JS runtime's `swjs_*` functions from the
+    // `javascript_kit` module (swjs_get_prop, swjs_call_function, ...)
+    module.any_imports_match(|i| i.module == "javascript_kit" && i.name.starts_with("swjs_"))
+}
+
+fn has_swift_runtime(module: &WasmModule) -> bool {
+    // SwiftWasm statically links the Swift runtime, whose reference counting and allocation entry points are in every
+    // module that uses a class or closure, and Swift's own symbols are mangled with a `$s` prefix (`$s4main5helloyyF`).
+    // Both only survive in the name section, but WASI modules have nothing else to tell them apart from C.
+    let is_swift_symbol = |name: &str| {
+        matches!(name, "swift_retain" | "swift_release" | "swift_allocObject")
+            || name.starts_with("$s")
+    };
+    module
+        .names
+        .functions
+        .iter()
+        .any(|name| is_swift_symbol(name))
+        || module.any_exports_match(|e| is_swift_symbol(e.name))
+}
+
+fn swift_environment(module: &WasmModule) -> Option<&'static str> {
+    // SwiftWasm always targets wasm32-unknown-wasi, running in the browser with a WASI polyfill alongside
+    // JavaScriptKit, or standalone in a WASI runtime
+    if imports_javascript_kit(module) {
+        Some("browser")
+    } else if wasi_version(module).is_some() {
+        Some("wasi")
+    } else {
+        None
+    }
+}
+
+fn is_julia(module: &WasmModule) -> bool {
+    // Best-effort, Julia's wasm support is experimental. The Julia runtime is written in C and its functions are
+    // consistently prefixed, with `jl_` for the runtime API (jl_init, jl_alloc_array_1d) and `julia_` for compiled
+    // Julia functions, which are visible when the module exports / imports them across the JS boundary.
+    let is_julia_name = |name: &str| name.starts_with("jl_") || name.starts_with("julia_");
+    module.any_imports_match(|i| is_julia_name(i.name))
+        || module.any_exports_match(|e| is_julia_name(e.name))
+}
+
+// Best-effort, for both Elm and Gren! Neither has a released wasm backend, only experiments, and the one convention
+// they can be expected to keep is their compilers' naming of top-level definitions, `$author$package$Module$name`, as
+// in their JS output. Only names that survive into the name section or exports can show it.
+fn has_definition_named(module: &WasmModule, packages: &[&str]) -> bool {
+    let is_definition = |name: &str| packages.iter().any(|package| name.starts_with(package));
+    module
+        .names
+        .functions
+        .iter()
+        .any(|name| is_definition(name))
+        || module.any_exports_match(|e| is_definition(e.name))
+}
+
+fn is_elm(module: &WasmModule) -> bool {
+    // elm/core and elm/browser are in every Elm program, and the application's own code is the `author/project`
+    // package
+    has_definition_named(module, &["$elm$core$", "$elm$browser$", "$author$project$"])
+}
+
+fn is_gren(module: &WasmModule) -> bool {
+    // Gren's core packages are published by gren-lang, e.g. `$gren_lang$core$Array$map`
+    has_definition_named(module, &["$gren_lang$"])
+}
+
+fn is_embind(module: &WasmModule) -> bool {
+    // Embind, Emscripten's C++ binding layer, registers every bound class / function at startup through imports such
+    // as `_embind_register_class`, which survive even when the rest of the emscripten_* runtime has been stripped.
+    // Newer Emscripten versions also export `_embind_initialize_bindings`, which the JS glue calls to run them. Plain C
+    // has no use for any of them, so they mark the module out as C++.
+    let is_embind_name = |name: &str| name.starts_with("_embind_") || name.starts_with("__embind_");
+    module.any_imports_match(|i| is_embind_name(i.name))
+        || module.any_exports_match(|e| is_embind_name(e.name))
+}
+
+fn is_qt(module: &WasmModule) -> bool {
+    // Qt for WebAssembly apps are Emscripten-compiled C++, but Qt's wasm platform plugin leaves its class names and
+    // logging categories among the strings in the data section, and the JS loader calls a few qt_ prefixed exports
+    module.any_exports_match(|e| e.name.starts_with("qt_"))
+        || module.data_contains(b"QWasmIntegration")
+        || module.data_contains(b"qt.qpa.wasm")
+}
+
+// whether `name` is wasm-bindgen's import of one of the web-sys / js-sys `methods`, i.e. `__wbg_<method>_<hash>`
+fn is_wbg_method(name: &str, methods: &[&str]) -> bool {
+    name.starts_with("__wbg_")
+        && methods
+            .iter()
+            .any(|m| name[6..].starts_with(m) && name[6 + m.len()..].starts_with('_'))
+}
+
+fn uses_bindgen_futures(module: &WasmModule) -> bool {
+    // wasm-bindgen-futures runs Rust futures on the JS event loop: it schedules tasks with `queueMicrotask` (or
+    // `Promise.resolve().then(..)` in older releases), and awaits JS promises by passing them closures, which show up
+    // as the `__wbindgen_cb_drop` import alongside `then`. Its source paths also end up in the data section, through
+    // panic locations.
+    let imports_method =
+        |methods: &[&str]| module.any_imports_match(|i| is_wbg_method(i.name, methods));
+    module.data_contains(b"wasm-bindgen-futures")
+        || imports_method(&["queueMicrotask"])
+        || (imports_method(&["then"])
+            && (imports_method(&["resolve"])
+                || module.any_imports_match(|i| i.name == "__wbindgen_cb_drop")))
+}
+
+fn rust_framework(module: &WasmModule) -> Option<&'static str> {
+    // Frameworks are split into several crates, whose paths end up in the data section through panic locations, e.g.
+    // `/cargo/registry/src/.../leptos_reactive-0.6.5/src/signal.rs`. This is best-effort: a release build with
+    // `panic = "abort"` and `panic_immediate_abort` leaves no paths behind. Checked in order, so a Bevy app with a Yew
+    // UI is Bevy.
+    const FRAMEWORKS: &[(&str, &[&[u8]])] = &[
+        ("bevy", &[b"bevy_ecs", b"bevy_render"]),
+        ("yew", &[b"/yew-0.", b"yew-macro"]),
+        (
+            "leptos",
+            &[b"leptos_reactive", b"leptos_dom", b"reactive_graph"],
+        ),
+        ("dioxus", &[b"dioxus-core", b"dioxus_core"]),
+        ("sycamore", &[b"sycamore-reactive", b"sycamore-core"]),
+    ];
+    FRAMEWORKS
+        .iter()
+        .find(|(_, paths)| paths.iter().any(|path| module.data_contains(path)))
+        .map(|(framework, _)| *framework)
+}
+
+fn rust_crates(module: &WasmModule) -> Vec<&'static str> {
+    // The ecosystem crates a wasm-bindgen app uses, from the JS functions they import through it - best-effort, as
+    // wasm-bindgen only imports what the app calls:
+    //  - "getrandom": its `js` backend fills buffers with `crypto.getRandomValues` in browsers, and
+    //    `crypto.randomFillSync` on Node
+    //  - "js-sys": the bindings to JS's builtins, which find the global object (`globalThis`) and construct `Function`s
+    //    (`new Function(..)`, imported as `newnoargs`)
+    //  - "web-sys": the bindings to the web APIs, which check types with `instanceof` for `dyn_into` and reach the DOM
+    //    through `document`, `createElement` and `addEventListener`
+    //  - "gloo": the toolkit of web-sys wrappers - its methods are web-sys's, so it is only seen in its crate paths
+    let imports_method =
+        |methods: &[&str]| module.any_imports_match(|i| is_wbg_method(i.name, methods));
+    let mut crates = vec![];
+    if imports_method(&["getRandomValues", "randomFillSync"]) {
+        crates.push("getrandom");
+    }
+    if imports_method(&["globalThis", "newnoargs"]) {
+        crates.push("js-sys");
+    }
+    if module.any_imports_match(|i| i.name.starts_with("__wbg_instanceof_"))
+        || imports_method(&["document", "createElement", "addEventListener"])
+    {
+        crates.push("web-sys");
+    }
+    if module.data_contains(b"/gloo-") {
+        crates.push("gloo");
+    }
+    crates
+}
+
+fn uses_canonical_abi(module: &WasmModule) -> bool {
+    // Core modules built to be wrapped in a component - by wit-bindgen, whatever the language - export the Canonical
+    // ABI's allocator (`cabi_realloc`, or `cabi_realloc_wit_bindgen_*` in newer versions) for the host to pass strings
+    // and lists in with, and `cabi_post_*` functions to free what exports return. wit-bindgen also embeds the world
+    // the module implements in a `component-type:*` custom section. These remain after the component wrapper has been
+    // taken off, or before it has been added.
+    module.any_exports_match(|e| {
+        e.name.starts_with("cabi_realloc") || e.name.starts_with("cabi_post_")
+    }) || module
+        .custom_sections
+        .iter()
+        .any(|name| name.starts_with("component-type"))
+}
+
+fn instrumentation(module: &WasmModule) -> Vec<&'static str> {
+    // Instrumented dev / test builds that have been published by mistake. LLVM's coverage and PGO instrumentation adds
+    // the `__llvm_profile_*` runtime, `__profc_*` / `__profd_*` counters and data for each function, and, in object
+    // files, `__llvm_covfun` / `__llvm_covmap` sections. AddressSanitizer and UndefinedBehaviorSanitizer add their
+    // `__asan_*` and `__ubsan_*` runtimes. These are C runtime symbols whatever the source language, so none of the
+    // rules look for them.
+    let symbols: Vec<&str> = module
+        .imports
+        .iter()
+        .map(|i| i.name)
+        .chain(module.exports.iter().map(|e| e.name))
+        .chain(module.names.functions.iter().copied())
+        .collect();
+    let has_symbol = |prefixes: &[&str]| {
+        symbols
+            .iter()
+            .any(|name| prefixes.iter().any(|prefix| name.starts_with(prefix)))
+    };
+
+    let mut kinds = vec![];
+    if has_symbol(&["__llvm_profile_", "__llvm_prf_", "__profc_", "__profd_"])
+        || module
+            .custom_sections
+            .iter()
+            .any(|name| name.starts_with("__llvm_cov"))
+    {
+        kinds.push("coverage");
+    }
+    if has_symbol(&["__asan_"]) {
+        kinds.push("asan");
+    }
+    if has_symbol(&["__ubsan_"]) {
+        kinds.push("ubsan");
+    }
+    kinds
+}
+
+fn is_gl_function(name: &str) -> bool {
+    let name = name.strip_prefix("emscripten_").unwrap_or(name);
+    name.len() > 2
+        && name.starts_with("gl")
+        && name[2..].starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+// A handful of GL imports may be incidental - a library that can draw, linked into an app that doesn't - so it takes a
+// cluster of them to call a module a GL app
+const GL_CLUSTER_SIZE: usize = 8;
+
+// entry points added in OpenGL ES 3.0, and so WebGL 2
+const GLES3_FUNCTIONS: &[&str] = &[
+    "glBindVertexArray",
+    "glDrawArraysInstanced",
+    "glDrawElementsInstanced",
+    "glDrawBuffers",
+    "glTexImage3D",
+    "glTexStorage2D",
+    "glBlitFramebuffer",
+    "glUniformBlockBinding",
+    "glGetUniformBlockIndex",
+    "glBindBufferBase",
+    "glVertexAttribIPointer",
+    "glFenceSync",
+];
+
+fn emscripten_gl_version(module: &WasmModule) -> Option<&'static str> {
+    // Emscripten's GL library is imported from `env` a function at a time, so a graphics-heavy app - typically C++,
+    // drawing directly or through SDL, GLFW, raylib or a game engine - has dozens of `gl*` imports (`emscripten_gl*`
+    // when called through `emscripten_GetProcAddress` / `emscripten_glGetProcAddress`). Creating a WebGL context, or
+    // looking GL functions up by name, is as good a sign on its own. The ES 3 entry points show it needs WebGL 2.
+    let gl_imports: Vec<&str> = module
+        .imports
+        .iter()
+        .filter(|i| i.module == "env" && is_gl_function(i.name))
+        .map(|i| i.name.strip_prefix("emscripten_").unwrap_or(i.name))
+        .collect();
+    let uses_gl = gl_imports.len() >= GL_CLUSTER_SIZE
+        || module.any_imports_match(|i| {
+            i.module == "env"
+                && matches!(
+                    i.name,
+                    "emscripten_webgl_create_context"
+                        | "emscripten_GetProcAddress"
+                        | "emscripten_glGetProcAddress"
+                        | "emscripten_webgl_get_proc_address"
+                )
+        });
+    if !uses_gl {
+        None
+    } else if gl_imports.iter().any(|name| GLES3_FUNCTIONS.contains(name)) {
+        Some("gles3")
+    } else {
+        Some("gles2")
+    }
+}
+
+// The syscalls Emscripten's JS library implements against its virtual file system: the path-based operations, which
+// stdio on its own (writing to stdout through `fd_write`) never needs
+const FS_SYSCALLS: [&str; 14] = [
+    "__syscall_openat",
+    "__syscall_stat64",
+    "__syscall_lstat64",
+    "__syscall_fstat64",
+    "__syscall_newfstatat",
+    "__syscall_getdents64",
+    "__syscall_mkdirat",
+    "__syscall_unlinkat",
+    "__syscall_rmdir",
+    "__syscall_renameat",
+    "__syscall_readlinkat",
+    "__syscall_faccessat",
+    "__syscall_chdir",
+    "__syscall_getcwd",
+];
+
+// A lone `__syscall_openat` may just be reading a config file, so it takes a few of them to call it file I/O
+const FS_CLUSTER_SIZE: usize = 3;
+
+fn emscripten_fs(module: &WasmModule) -> Option<&'static str> {
+    // Emscripten emulates a POSIX file system in JS, in memory (MEMFS) unless the app mounts another backend, so the
+    // syscalls are imported from `env` by name. IndexedDB persistence (IDBFS) is mounted from JS, so isn't visible
+    // here, but the `emscripten_idb_*` functions, for storing files in IndexedDB directly, are.
+    let fs_imports = module
+        .imports
+        .iter()
+        .filter(|i| i.module == "env" && FS_SYSCALLS.contains(&i.name))
+        .count();
+    if module.any_imports_match(|i| i.module == "env" && i.name.starts_with("emscripten_idb_")) {
+        Some("idb")
+    } else if fs_imports >= FS_CLUSTER_SIZE {
+        Some("memfs")
+    } else {
+        None
+    }
+}
+
+fn graphics_backends(module: &WasmModule) -> Vec<&'static str> {
+    // Independent of the language, the imports show which browser graphics API a module draws with. Emscripten imports
+    // the GL functions by name (`glDrawArrays`, or `emscripten_glDrawArrays` via its GL emulation) and its WebGPU
+    // bindings as `wgpu*`, while wasm-bindgen imports each web-sys method it calls as `__wbg_<method>_<hash>`, so Rust
+    // (including wgpu itself, which can fall back to WebGL) is recognised by WebGPU and WebGL specific method names
+    let mut backends = vec![];
+    if module.any_imports_match(|i| {
+        i.name.starts_with("wgpu")
+            || i.name.starts_with("emscripten_webgpu_")
+            || is_wbg_method(
+                i.name,
+                &[
+                    "requestAdapter",
+                    "createRenderPipeline",
+                    "createShaderModule",
+                ],
+            )
+    }) {
+        backends.push("webgpu");
+    }
+    if module.any_imports_match(|i| {
+        is_gl_function(i.name)
+            || i.name.starts_with("emscripten_webgl_")
+            || is_wbg_method(i.name, &["createShader", "drawArrays", "drawElements"])
+    }) {
+        backends.push("webgl");
+    }
+    backends
+}
+
+fn source_file_has_extension(module: &WasmModule, extensions: &[&str]) -> bool {
+    // A weak signal, but the source map URL is often derived from the name of the source file, e.g.
+    // `main.cpp.wasm.map` or `lib.rs.map`
+    let url = match module.source_map_url.as_deref() {
+        Some(url) => url,
+        None => return false,
+    };
+    extensions
+        .iter()
+        .any(|ext| url.contains(&format!(".{ext}.")) || url.ends_with(&format!(".{ext}")))
+}
+
+fn is_hand_written(module: &WasmModule) -> bool {
+    // Modules assembled from WAT (e.g. with wabt's wat2wasm) are typically tutorials, demos or tiny hand-optimised
+    // helpers. Compilers leave a producers section behind, and their runtimes bring along a bunch of imports and
+    // support exports (__heap_base, _start, etc.), whereas hand-written modules are tiny and export a few plain names.
+    let is_plain_name = |name: &str| {
+        !name.starts_with('_') && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+
+    !module.has_custom_section("producers")
+        && module.size <= 2048
+        && module.imports.len() <= 4
+        && module.exports.len() <= 16
+        && !module.exports.is_empty()
+        && !module.any_exports_match(|e| !is_plain_name(e.name))
+}
+
+// The fraction of the toolchain markers that a module lacks, from 0.0 when it has them all to 1.0 for none. Every
+// compiler leaves at least one behind: a producers section, mangled symbol names, imports from a language runtime or
+// host ABI, the linker's support exports, or custom sections of its own (DWARF, `linking`, `target_features`). A
+// module without any was most likely assembled by hand, whereas an Unknown module that has some is from a toolchain
+// there's no detector for yet.
+fn toolchain_marker_absence(module: &WasmModule) -> f32 {
+    let is_mangled = |name: &str| {
+        name.starts_with("_Z")
+            || name.starts_with("_R")
+            || name.contains("::")
+            || name.contains(['.', '$', '@', '<'])
+    };
+    let is_runtime_import = |i: &Import| {
+        i.module.starts_with("wasi")
+            || matches!(i.module, "wbg" | "go" | "gojs" | "__wbindgen_placeholder__")
+            || i.name.starts_with("__")
+            || i.name.contains("emscripten")
+            || i.name.contains("wbindgen")
+    };
+    let markers = [
+        module.has_custom_section("producers"),
+        module.names.functions.iter().any(|name| is_mangled(name))
+            || module.any_exports_match(|e| is_mangled(e.name)),
+        module.any_imports_match(is_runtime_import),
+        module.any_exports_match(|e| e.name.starts_with('_')),
+        module
+            .custom_sections
+            .iter()
+            .any(|name| !matches!(*name, "name" | "sourceMappingURL")),
+    ];
+    markers.iter().filter(|present| !**present).count() as f32 / markers.len() as f32
+}
+
+// Modules that lack every toolchain marker, but are a little larger than is_hand_written allows. Only small, simple
+// modules count - a large module without any markers has more likely been post-processed to remove them.
+fn has_no_toolchain_markers(module: &WasmModule) -> bool {
+    module.size <= 16 * 1024
+        && module.defined_functions > 0
+        && module.defined_functions <= 64
+        && toolchain_marker_absence(module) == 1.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tier {
+    // a specific toolchain signature, or several signals that agree
+    Strong,
+    // a single loose signal, e.g. a naming convention or a substring match, which is only used in lenient mode
+    Weak,
+}
+
+// Strict mode only uses the strong rules, so that a module is either classified with confidence or left Unknown, for
+// when precision matters more than coverage. Lenient mode, the default, also tries the weak rules.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Mode {
+    Strict,
+    #[default]
+    Lenient,
+}
+
+// A single detection heuristic. Rules are tried in order and the first one that matches determines the language, so
+// the precise signals come first and the fuzzy fallbacks last.
+pub struct Rule {
+    // a stable identifier, reported as the evidence for a classification
+    pub id: &'static str,
+    pub language: Language,
+    // how much a match can be trusted, from 1.0 for an unambiguous toolchain signature down to ~0.5 for the guesses
+    pub confidence: f32,
+    pub tier: Tier,
+    pub description: &'static str,
+    matches: fn(&WasmModule) -> bool,
+    // whether the rule looks beyond the import / export sections, e.g. at custom sections or the overall size, which
+    // means it can't be relied on for a prefix of the module. These rules go after the import / export rules.
+    pub whole_module: bool,
+    // real-world modules that motivated the rule, shown by `explain`
+    pub notes: &'static [&'static str],
+}
+
+pub const RULES: &[Rule] = &[
+    Rule {
+        id: "emscripten.import",
+        language: Language::Emscripten,
+        confidence: 1.0,
+        tier: Tier::Strong,
+        description: "imports a function with 'emscripten' in its name",
+        matches: is_emscripten,
+        whole_module: false,
+        notes: &["the JS glue Emscripten generates provides a number of emscripten_* runtime functions"],
+    },
+    Rule {
+        id: "blazor.import",
+        language: Language::Blazor,
+        confidence: 1.0,
+        tier: Tier::Strong,
+        description: "imports a function with 'blazor' in its name",
+        matches: is_blazor,
+        whole_module: false,
+        notes: &[],
+    },
+    Rule {
+        id: "rust.wasm_bindgen",
+        language: Language::Rust,
+        confidence: 1.0,
+        tier: Tier::Strong,
+        description: "imports or exports wasm-bindgen glue (wbg / wbindgen)",
+        matches: is_rust,
+        whole_module: false,
+        notes: &[],
+    },
+    Rule {
+        id: "go.import_module",
+        language: Language::Go,
+        confidence: 1.0,
+        tier: Tier::Strong,
+        description: "imports Go's runtime.* / syscall/js.* bridge from the 'go' or 'gojs' module",
+        matches: is_go,
+        whole_module: false,
+        notes: &[],
+    },
+    Rule {
+        id: "swift.javascriptkit",
+        language: Language::Swift,
+        confidence: 1.0,
+        tier: Tier::Strong,
+        description: "imports JavaScriptKit's swjs_* functions from the 'javascript_kit' module",
+        matches: imports_javascript_kit,
+        whole_module: false,
+        notes: &[],
+    },
+    Rule {
+        id: "assemblyscript.hyphenate",
+        language: Language::AssemblyScript,
+        confidence: 0.8,
+        tier: Tier::Weak,
+        description: "exports 'hyphenate', as the Hyphenopoly bundles do",
+        matches: is_assemblyscript,
+        whole_module: false,
+        notes: &["https://github.com/mnater/Hyphenopoly - found on a number of websites, in a variety of bundles"],
+    },
+    Rule {
+        id: "assemblyscript.abort",
+        language: Language::AssemblyScript,
+        confidence: 0.9,
+        tier: Tier::Strong,
+        description: "imports env.abort(msg, file, line, column: i32)",
+        matches: imports_assemblyscript_abort,
+        whole_module: false,
+        notes: &[],
+    },
+    Rule {
+        id: "assemblyscript.arguments_glue",
+        language: Language::AssemblyScript,
+        confidence: 0.95,
+        tier: Tier::Strong,
+        description: "exports __setArgumentsLength (or __setargc), for JS to call functions with optional parameters",
+        matches: exports_assemblyscript_arguments_glue,
+        whole_module: false,
+        notes: &[],
+    },
+    Rule {
+        id: "dart.dart2wasm",
+        language: Language::Dart,
+        confidence: 1.0,
+        tier: Tier::Strong,
+        description: "a GC module importing dart2wasm's JS interop, or exporting $invokeMain",
+        matches: is_dart,
+        whole_module: false,
+        notes: &[],
+    },
+    Rule {
+        id: "java.teavm",
+        language: Language::Java,
+        confidence: 1.0,
+        tier: Tier::Strong,
+        description: "imports TeaVM's runtime from the 'teavm', 'teavmMath' or 'teavmJso' modules",
+        matches: is_teavm,
+        whole_module: false,
+        notes: &[],
+    },
+    Rule {
+        id: "java.jwebassembly",
+        language: Language::Java,
+        confidence: 0.8,
+        tier: Tier::Strong,
+        description: "imports JWebAssembly's NonGC polyfill, or JVM class names (java/lang/...)",
+        matches: imports_jwebassembly,
+        whole_module: false,
+        notes: &[],
+    },
+    Rule {
+        id: "scheme.runtime",
+        language: Language::Scheme,
+        confidence: 0.6,
+        tier: Tier::Weak,
+        description: "imports Scheme-named functions from an 'rt' runtime module",
+        matches: is_scheme,
+        whole_module: false,
+        notes: &[],
+    },
+    Rule {
+        id: "julia.runtime",
+        language: Language::Julia,
+        confidence: 0.6,
+        tier: Tier::Weak,
+        description: "imports or exports jl_ / julia_ runtime functions",
+        matches: is_julia,
+        whole_module: false,
+        notes: &[],
+    },
+    Rule {
+        id: "emscripten.embind",
+        language: Language::Emscripten,
+        confidence: 0.9,
+        tier: Tier::Strong,
+        description: "imports or exports Embind _embind_* functions",
+        matches: is_embind,
+        whole_module: false,
+        notes: &[],
+    },
+    Rule {
+        id: "emscripten.stack",
+        language: Language::Emscripten,
+        confidence: 0.95,
+        tier: Tier::Strong,
+        description: "exports stackSave / stackRestore / emscripten_stack_* functions",
+        matches: manages_emscripten_stack,
+        whole_module: false,
+        notes: &[],
+    },
+    Rule {
+        id: "emscripten.invoke",
+        language: Language::Emscripten,
+        confidence: 0.95,
+        tier: Tier::Strong,
+        description: "imports the env.invoke_* thunks of JS-based exception handling",
+        matches: uses_js_exceptions,
+        whole_module: false,
+        notes: &[],
+    },
+    Rule {
+        id: "emscripten.likely",
+        language: Language::Emscripten,
+        confidence: 0.5,
+        tier: Tier::Weak,
+        description: "minified a.a / env.a imports, or a malloc export",
+        matches: is_likely_emscripten,
+        whole_module: false,
+        notes: &[
+            "https://tweet2doom.github.io/t2d-explorer.wasm (a.a / a.b) - the tweet2doom repo shows strong evidence of Emscripten",
+            "https://graphonline.ru/script/Graphoffline.Emscripten.wasm (a.a / a.b) - the clue is in the filename",
+            "https://wsr-starfinder.com (a.a / a.b) - stellarium-web-engine, which builds with Emscripten",
+            "https://tx.me (env.a / env.b) - rlottie, compiled with Emscripten",
+            "https://demo.harmonicvision.com (env.a / env.b) - Emscripten is mentioned in the page source",
+            "https://webcamera.io (env.a / env.b) - uses FFmpeg, built with Emscripten",
+        ],
+    },
+    Rule {
+        id: "emscripten.side_module",
+        language: Language::Emscripten,
+        confidence: 0.8,
+        tier: Tier::Strong,
+        description: "a position independent side module, importing env.__memory_base or env.memory and its function table, exporting __wasm_apply_data_relocs or with a dylink section",
+        matches: is_emscripten_side_module,
+        whole_module: false,
+        notes: &[],
+    },
+    Rule {
+        id: "emscripten.static_constructors",
+        language: Language::Emscripten,
+        confidence: 0.7,
+        tier: Tier::Weak,
+        description: "exports __wasm_call_ctors, the LLVM entry point for static constructors",
+        matches: exports_static_constructors,
+        whole_module: false,
+        notes: &[],
+    },
+    // the first of the whole-module rules, though the DWARF rules are tried ahead of all the others - see
+    // matching_rule_with_mode
+    Rule {
+        id: "rust.dwarf",
+        language: Language::Rust,
+        confidence: 1.0,
+        tier: Tier::Strong,
+        description: "DW_LANG_Rust in the DWARF compilation unit (--deep)",
+        matches: is_rust_dwarf,
+        whole_module: true,
+        notes: &[],
+    },
+    Rule {
+        id: "swift.dwarf",
+        language: Language::Swift,
+        confidence: 1.0,
+        tier: Tier::Strong,
+        description: "DW_LANG_Swift in the DWARF compilation unit (--deep)",
+        matches: is_swift_dwarf,
+        whole_module: true,
+        notes: &[],
+    },
+    Rule {
+        id: "clang.dwarf",
+        language: Language::Emscripten,
+        confidence: 1.0,
+        tier: Tier::Strong,
+        description: "a C or C++ DW_LANG_* in the DWARF compilation unit (--deep)",
+        matches: is_clang_dwarf,
+        whole_module: true,
+        notes: &["Emscripten and wasi-sdk debug builds alike, as with clang.wasi_main"],
+    },
+    Rule {
+        id: "emscripten.qt",
+        language: Language::Emscripten,
+        confidence: 0.9,
+        tier: Tier::Strong,
+        description: "Qt for WebAssembly symbols in the exports or data",
+        matches: is_qt,
+        whole_module: true,
+        notes: &[],
+    },
+    Rule {
+        id: "rust.toolchain",
+        language: Language::Rust,
+        confidence: 0.95,
+        tier: Tier::Strong,
+        description: "rustc in the producers section, or Rust runtime symbols",
+        matches: is_rust_toolchain,
+        whole_module: true,
+        notes: &[],
+    },
+    Rule {
+        id: "swift.runtime",
+        language: Language::Swift,
+        confidence: 0.9,
+        tier: Tier::Strong,
+        description: "Swift runtime (swift_retain / swift_release) or $s-mangled symbols",
+        matches: has_swift_runtime,
+        whole_module: true,
+        notes: &[],
+    },
+    Rule {
+        id: "go.wasip1",
+        language: Language::Go,
+        confidence: 0.9,
+        tier: Tier::Strong,
+        description: "WASI imports, with Go's _rt0_wasm_wasip1 entry point, or main.main and runtime.* functions",
+        matches: has_go_runtime,
+        whole_module: true,
+        notes: &["GOOS=wasip1 builds, which don't import Go's JS bridge"],
+    },
+    Rule {
+        id: "java.jwebassembly_names",
+        language: Language::Java,
+        confidence: 0.8,
+        tier: Tier::Strong,
+        description: "JVM class names (java/lang/...) among the function names, as JWebAssembly emits them",
+        matches: has_jvm_function_names,
+        whole_module: true,
+        notes: &["JWebAssembly modules built for GC, which don't import the NonGC polyfill"],
+    },
+    Rule {
+        id: "clang.wasi_main",
+        language: Language::Emscripten,
+        confidence: 0.9,
+        tier: Tier::Strong,
+        description: "defines clang's __main_argc_argv / __original_main entry points for wasi-libc",
+        matches: is_clang_wasi_program,
+        whole_module: true,
+        notes: &["wasi-sdk's C / C++ programs, which share Emscripten's clang toolchain rather than its JS glue"],
+    },
+    Rule {
+        id: "rust.source_map",
+        language: Language::Rust,
+        confidence: 0.5,
+        tier: Tier::Weak,
+        description: "source map URL refers to a .rs file",
+        matches: |m| source_file_has_extension(m, &["rs"]),
+        whole_module: true,
+        notes: &[],
+    },
+    Rule {
+        id: "emscripten.source_map",
+        language: Language::Emscripten,
+        confidence: 0.5,
+        tier: Tier::Weak,
+        description: "source map URL refers to a C / C++ file",
+        matches: |m| source_file_has_extension(m, &["c", "cc", "cpp", "cxx"]),
+        whole_module: true,
+        notes: &[],
+    },
+    Rule {
+        id: "go.source_map",
+        language: Language::Go,
+        confidence: 0.5,
+        tier: Tier::Weak,
+        description: "source map URL refers to a .go file",
+        matches: |m| source_file_has_extension(m, &["go"]),
+        whole_module: true,
+        notes: &[],
+    },
+    Rule {
+        id: "assemblyscript.source_map",
+        language: Language::AssemblyScript,
+        confidence: 0.5,
+        tier: Tier::Weak,
+        description: "source map URL refers to a .ts file",
+        matches: |m| source_file_has_extension(m, &["ts"]),
+        whole_module: true,
+        notes: &[],
+    },
+    Rule {
+        id: "elm.definitions",
+        language: Language::Elm,
+        confidence: 0.4,
+        tier: Tier::Weak,
+        description: "has functions named like Elm's compiled definitions, e.g. $elm$core$List$map",
+        matches: is_elm,
+        whole_module: true,
+        notes: &["best-effort, Elm has no released wasm backend"],
+    },
+    Rule {
+        id: "gren.definitions",
+        language: Language::Gren,
+        confidence: 0.4,
+        tier: Tier::Weak,
+        description: "has functions named like Gren's compiled definitions, e.g. $gren_lang$core$Array$map",
+        matches: is_gren,
+        whole_module: true,
+        notes: &["best-effort, Gren has no released wasm backend"],
+    },
+    Rule {
+        id: "handwritten.profile",
+        language: Language::HandWritten,
+        confidence: 0.4,
+        tier: Tier::Weak,
+        description: "tiny, with no producers section, few imports and only plain export names",
+        matches: is_hand_written,
+        whole_module: true,
+        notes: &[],
+    },
+    Rule {
+        id: "handwritten.no_toolchain",
+        language: Language::HandWritten,
+        confidence: 0.3,
+        tier: Tier::Weak,
+        description: "small and simple, without any of the markers that toolchains leave behind",
+        matches: has_no_toolchain_markers,
+        whole_module: true,
+        notes: &[],
+    },
+    // Unknown modules
+    // 2735d1055ef617dbb1e84cdfa8eb5a9c05f50201a7aa8c06d44533166124fec6.wasm => https://tikzjax.com / webjs / Pascal
+
+    // b8ea049ced002e39f3e32203c3d08f2efa964437887c92c39dd22e50945d7438.wasm => https://github.com/gasman/jsspeccy3 / AssemblyScript
+];
+
+impl Rule {
+    pub fn matches(&self, module: &WasmModule) -> bool {
+        (self.matches)(module)
+    }
+}
+
+fn rule(id: &str) -> &'static Rule {
+    RULES.iter().find(|rule| rule.id == id).unwrap()
+}
+
+// The vast majority of modules are identified by the first few rules, which only look at import names. Checking those
+// in a single pass over the imports, without the per-rule passes (and allocations) of the full chain, speeds up large
+// crawls considerably. This must agree with the full chain: it only returns a rule once every rule ahead of it has
+// been ruled out, and on a miss the chain resumes after the rules it has fully evaluated (emscripten, blazor and
+// wasm-bindgen).
+const PRESCREENED: usize = 3;
+
+fn prescreen(module: &WasmModule) -> Option<&'static Rule> {
+    let mut blazor = false;
+    let mut rust = false;
+    for import in &module.imports {
+        if import.name.contains("emscripten") {
+            return Some(rule("emscripten.import"));
+        }
+        blazor |= import.name.contains("blazor");
+        rust |= import.name.contains("wbindgen")
+            || import.name.contains("wbg")
+            || import.module == "wbg"
+            || import.module == "wbindgen";
+    }
+
+    if blazor {
+        Some(rule("blazor.import"))
+    } else if rust || module.any_exports_match(|e| e.name.contains("wbindgen")) {
+        Some(rule("rust.wasm_bindgen"))
+    } else {
+        None
+    }
+}
+
+fn dwarf_rule(module: &WasmModule) -> Option<&'static Rule> {
+    match dwarf_source_language(module)?.0 {
+        Language::Rust => Some(rule("rust.dwarf")),
+        Language::Swift => Some(rule("swift.dwarf")),
+        _ => Some(rule("clang.dwarf")),
+    }
+}
+
+// How far a weak import / export pattern is trusted on a stripped module, relative to its confidence
+const STRIPPED_PATTERN_FACTOR: f32 = 0.8;
+
+impl Rule {
+    // The rule's confidence in a match on this particular module. On a stripped module a weak import / export pattern
+    // is all there is to go on, with no names or producers to contradict it, so it is trusted less. Strong rules match
+    // signatures that are conclusive on their own, and whole-module rules look at more than just the names.
+    pub fn confidence_for(&self, module: &WasmModule) -> f32 {
+        if module.is_stripped() && self.tier == Tier::Weak && !self.whole_module {
+            self.confidence * STRIPPED_PATTERN_FACTOR
+        } else {
+            self.confidence
+        }
+    }
+}
+
+pub fn matching_rule(module: &WasmModule) -> Option<&'static Rule> {
+    matching_rule_with_mode(module, Mode::Lenient)
+}
+
+// The source language in the DWARF debug info outranks everything else, so its rules are tried first, wherever they
+// are in RULES. They can't match without ParseOptions::deep, which leaves the order as is. The pre-screened rules are
+// all strong, so strict mode only has to skip the weak rules after them.
+pub fn matching_rule_with_mode(module: &WasmModule, mode: Mode) -> Option<&'static Rule> {
+    dwarf_rule(module)
+        .or_else(|| prescreen(module))
+        .or_else(|| {
+            RULES[PRESCREENED..]
+                .iter()
+                .filter(|rule| mode == Mode::Lenient || rule.tier == Tier::Strong)
+                .find(|rule| rule.matches(module))
+        })
+}
+
+// Every rule that matches the module, in RULES order, for measuring how often each of them fires across a corpus. The
+// one that classify picks is normally the first, but not when a DWARF rule matches, as they are tried ahead of the
+// others, or in strict mode, which skips the weak rules.
+pub fn matching_rules(module: &WasmModule) -> Vec<&'static Rule> {
+    RULES.iter().filter(|rule| rule.matches(module)).collect()
+}
+
+// Classifies from the first part of a module, e.g. fetched with an HTTP range request. The import and export sections
+// come before the code and data, so a few KB is usually enough to reach the end of them. Returns None when more bytes
+// are needed: when the prefix stops before the end of the export section, or when no import / export rule matches -
+// there's no telling whether the prefix is the whole module, so rules that need the remaining sections (and Unknown)
+// are left to infer_language once the whole module has been fetched.
+pub fn infer_language_prefix(buf: &[u8]) -> Option<Language> {
+    let module = parse_wasm(buf);
+    if !module.exports_read {
+        return None;
+    }
+    // the imports / exports are complete, so any import / export rule that matches would also match the whole module,
+    // and none of the rules ahead of it can match the whole module either
+    matching_rule(&module)
+        .filter(|rule| !rule.whole_module)
+        .map(|rule| rule.language)
+}
+
+// Every language with a strong signature in the module, in rule order, so normally just the one classify picks. Modules
+// linked from several languages - say a Rust crate built for Emscripten, calling into C++ - have more than one, and
+// picking a single language for them misrepresents the rest. Weak patterns are left out, as they overlap by nature.
+pub fn infer_all_languages(module: &WasmModule) -> Vec<Language> {
+    let mut languages = vec![];
+    for rule in RULES.iter().filter(|rule| rule.tier == Tier::Strong) {
+        if !languages.contains(&rule.language) && rule.matches(module) {
+            languages.push(rule.language);
+        }
+    }
+    languages
+}
+
+// The languages of a module with the strong signatures of more than one, for its `polyglot` hint. Only a module decided
+// by a strong rule counts, and the other strong rules are only all tried once one of another language has matched,
+// so most modules cost a single pass over the strong rules, which stops at the first match.
+pub(crate) fn polyglot_languages(module: &WasmModule, rule: &Rule) -> Option<Vec<Language>> {
+    if rule.tier != Tier::Strong {
+        return None;
+    }
+    RULES
+        .iter()
+        .any(|other| {
+            other.tier == Tier::Strong && other.language != rule.language && other.matches(module)
+        })
+        .then(|| infer_all_languages(module))
+}
+
+pub fn infer_language(module: &WasmModule) -> Language {
+    infer_language_with_mode(module, Mode::Lenient)
+}
+
+// The kinds of module that are told apart by what they are, ahead of any rule: empty modules, coredumps, split-out
+// debug info and unlinked object files
+pub(crate) fn special_language(module: &WasmModule) -> Option<Language> {
+    if module.is_empty() {
+        Some(Language::Empty)
+    } else if module.is_coredump() {
+        Some(Language::Coredump)
+    } else if module.is_debug_info() {
+        Some(Language::DebugInfo)
+    } else if module.is_object_file() {
+        Some(Language::ObjectFile)
+    } else {
+        None
+    }
+}
+
+pub fn infer_language_with_mode(module: &WasmModule, mode: Mode) -> Language {
+    if let Some(language) = special_language(module) {
+        return language;
+    }
+    matching_rule_with_mode(module, mode).map_or(Language::Unknown, |rule| rule.language)
+}
+
+// The layer that glues the module to JavaScript, which follows from the source language for most, but not all, of
+// them - e.g. embind, which only some Emscripten modules use
+pub(crate) fn infer_js_binding(module: &WasmModule) -> Option<&'static str> {
+    if module.any_imports_match(|i| {
+        i.module == "wbg" || i.module == "__wbindgen_placeholder__" || i.name.starts_with("__wbg_")
+    }) {
+        Some("wasm-bindgen")
+    } else if is_embind(module) {
+        Some("embind")
+    } else if module
+        .any_imports_match(|i| i.module == "javascript_kit" || i.name.starts_with("swjs_"))
+    {
+        Some("javascriptkit")
+    } else if module.any_imports_match(|i| {
+        (i.module == "go" || i.module == "gojs") && i.name.starts_with("syscall/js.")
+    }) {
+        Some("syscall/js")
+    } else {
+        None
+    }
+}
+
+fn wasi_version(module: &WasmModule) -> Option<&'static str> {
+    // Preview 2 imports are named after WIT interfaces (`wasi:cli/environment@0.2.0`, `wasi:http/types`), as seen in
+    // the core module that wit-bindgen produces before it is wrapped as a component, and the component's own imported
+    // interfaces. Preview 1 has a single module.
+    if module.any_imports_match(|i| i.module.starts_with("wasi:"))
+        || module
+            .component
+            .as_ref()
+            .is_some_and(|c| c.imports.iter().any(|i| i.starts_with("wasi:")))
+    {
+        Some("preview2")
+    } else if module.any_imports_match(|i| i.module == "wasi_snapshot_preview1") {
+        Some("preview1")
+    } else if module.any_imports_match(|i| i.module == "wasi_unstable") {
+        Some("preview0")
+    } else {
+        None
+    }
+}
+
+fn is_wasi_module(name: &str) -> bool {
+    name.starts_with("wasi:") || name == "wasi_snapshot_preview1" || name == "wasi_unstable"
+}
+
+pub(crate) fn infer_runnability(module: &WasmModule) -> Option<&'static str> {
+    // What it takes to run the module, from the functions it imports - an imported memory, table or global is just
+    // storage, which any runtime can supply:
+    //  - "standalone": no imported functions, so it is pure computation that any host can instantiate
+    //  - "wasi": only WASI functions, which the non-browser runtimes (wasmtime, wasmer, WasmEdge, Node's `wasi`)
+    //    provide
+    //  - "needs-js-glue": anything else, such as Emscripten's `env` runtime, wasm-bindgen's `wbg` shims or Go's `gojs`
+    //    bridge, which only the JS generated alongside the module implements. Imports from other hosts (a plugin
+    //    system's host functions, say) count too, as a runtime would need the same kind of glue written for it.
+    //    Emscripten side modules do whatever they import, as they can only be loaded by its dynamic linker, in JS.
+    // Components say what they need in their WIT imports instead.
+    if module.component.is_some() {
+        return None;
+    }
+    if is_emscripten_side_module(module) {
+        return Some("needs-js-glue");
+    }
+    let mut functions = module
+        .imports
+        .iter()
+        .filter(|i| matches!(i.ty, TypeRef::Func(_)))
+        .peekable();
+    Some(if functions.peek().is_none() {
+        "standalone"
+    } else if functions.all(|i| is_wasi_module(i.module)) {
+        "wasi"
+    } else {
+        "needs-js-glue"
+    })
+}
+
+fn object_file_source_language(module: &WasmModule) -> Option<&'static str> {
+    // Best-effort. Object files are classified before linking, so the runtime imports and exports the rules look for
+    // aren't there yet, but the symbol names give the language away through their mangling: Rust's legacy scheme is
+    // the Itanium one plus a trailing hash (`_ZN4core3fmt5write17h0123456789abcdefE`), its v0 scheme starts `_R`, and
+    // C++ uses plain Itanium `_Z` names. Unmangled names are most likely C.
+    let names = module
+        .names
+        .functions
+        .iter()
+        .copied()
+        .chain(module.imports.iter().map(|i| i.name))
+        .chain(module.exports.iter().map(|e| e.name));
+    let is_rust_legacy = |name: &str| {
+        name.starts_with("_ZN")
+            && name.len() > 20
+            && name.ends_with('E')
+            && name[name.len() - 20..].starts_with("17h")
+    };
+
+    let mut language = None;
+    for name in names {
+        if name.starts_with("_R") || is_rust_legacy(name) {
+            return Some("rust");
+        } else if name.starts_with("_Z") {
+            language = Some("cpp");
+        } else if language.is_none() && !name.starts_with("__") {
+            language = Some("c");
+        }
+    }
+    language
+}
+
+fn is_asyncified(module: &WasmModule) -> bool {
+    // Binaryen's Asyncify pass (Emscripten's -sASYNCIFY, or wasm-opt --asyncify on anything else) instruments the code
+    // so that it can unwind and rewind the stack, and exports the functions that drive it. JSPI needs no
+    // instrumentation at all - the suspending is done by the engine - so it leaves nothing in the module to detect.
+    [
+        "asyncify_start_unwind",
+        "asyncify_stop_unwind",
+        "asyncify_start_rewind",
+        "asyncify_stop_rewind",
+    ]
+    .iter()
+    .all(|name| module.any_exports_match(|e| e.name == *name))
+}
+
+fn wasi_exec_model(module: &WasmModule) -> Option<&'static str> {
+    // WASI commands export `_start`, which runs main once and exits, whereas reactors (clang's -mexec-model=reactor,
+    // Rust's cdylib crates) export `_initialize` to set up libc, then stay resident for the host to call into. Failing
+    // either, clang's main entry points (see has_wasi_libc_main) still show that it is a program with a main.
+    if module.any_exports_match(|e| e.name == "_start") {
+        Some("command")
+    } else if module.any_exports_match(|e| e.name == "_initialize") {
+        Some("reactor")
+    } else if has_wasi_libc_main(module) {
+        Some("command")
+    } else {
+        None
+    }
+}
+
+fn c_startup(module: &WasmModule) -> Option<&'static str> {
+    // Whether a C / C++ module was linked against libc's startup code, as the clang driver does for programs, or with
+    // wasm-ld on its own (`-nostdlib`, `--no-entry`), as firmware-style and plugin modules are:
+    //  - "hosted": crt1's `_start` or `_initialize`, or clang's main entry points (see has_wasi_libc_main), which only
+    //    libc's startup calls
+    //  - "freestanding": none of those, and no WASI or Emscripten imports either, which libc would need for its I/O.
+    //    wasm-ld still synthesises `__wasm_call_ctors` for them, so it doesn't count as startup code.
+    // Emscripten's own modules are neither: its JS glue does the startup, so there are no startup symbols to go on.
+    if has_wasi_libc_main(module)
+        || module.any_exports_match(|e| e.name == "_start" || e.name == "_initialize")
+    {
+        Some("hosted")
+    } else if wasi_version(module).is_none()
+        && !module.any_imports_match(|i| {
+            i.name.contains("emscripten") || i.name.starts_with("__syscall_")
+        })
+        && !is_embind(module)
+        && !uses_js_exceptions(module)
+        && !is_emscripten_side_module(module)
+        && emscripten_output_mode(module) != Some("minified")
+    {
+        Some("freestanding")
+    } else {
+        None
+    }
+}
+
+fn toolchain(module: &WasmModule, language: &Language) -> Option<&'static str> {
+    // What compiled the module, as opposed to what it was written in. Most languages have the one compiler, so where
+    // the rules only had weak signals to go on (a minified Emscripten module, a stripped Rust one) it is that. The C
+    // family is split into Emscripten and wasi-sdk, which is told apart by its WASI imports and none of Emscripten's
+    // own, and Rust by whether it was bound to JS with wasm-bindgen. Languages without a single toolchain to name,
+    // e.g. hand-written modules, have none.
+    let toolchain = match language {
+        Language::Emscripten => {
+            if wasi_version(module).is_some()
+                && !is_emscripten(module)
+                && !manages_emscripten_stack(module)
+                && !is_embind(module)
+            {
+                "wasi-sdk"
+            } else {
+                "emscripten"
+            }
+        }
+        Language::Rust => {
+            if infer_js_binding(module) == Some("wasm-bindgen") {
+                "rustc+wasm-bindgen"
+            } else {
+                "rustc"
+            }
+        }
+        Language::Go if is_tinygo(module) => "tinygo",
+        Language::Go => "go",
+        Language::AssemblyScript => "asc",
+        Language::Blazor => "dotnet",
+        Language::Swift => "swiftwasm",
+        Language::Dart => "dart2wasm",
+        Language::Java if is_teavm(module) => "teavm",
+        Language::Java if is_jwebassembly(module) => "jwebassembly",
+        _ => return None,
+    };
+    Some(toolchain)
+}
+
+pub(crate) fn infer_hints(module: &WasmModule, language: &Language) -> Hints {
+    let mut hints = Hints::new();
+
+    if let Some(url) = &module.source_map_url {
+        hints.insert("source_map", url.clone());
+    }
+    if let Some(name) = module.names.module {
+        hints.insert("module_name", name.to_string());
+    }
+    if let Some(id) = &module.build_id {
+        hints.insert("build_id", hex(id));
+    }
+    if let Some(unit) = &module.dwarf {
+        hints.insert("debug_format", format!("dwarf{}", unit.version));
+    }
+    if is_asyncified(module) {
+        hints.insert("async_mechanism", "asyncify".to_string());
+    }
+    if let Some(version) = wasi_version(module) {
+        hints.insert("wasi_version", version.to_string());
+        if let Some(model) = wasi_exec_model(module) {
+            hints.insert("wasi_exec_model", model.to_string());
+        }
+    }
+
+    if let Some(component) = &module.component {
+        if !component.imports.is_empty() {
+            hints.insert("wit_imports", component.imports.join(","));
+        }
+        if !component.exports.is_empty() {
+            hints.insert("wit_exports", component.exports.join(","));
+        }
+    }
+
+    if let Some(toolchain) = toolchain(module, language) {
+        hints.insert("toolchain", toolchain.to_string());
+    }
+
+    if uses_canonical_abi(module) {
+        hints.insert("component_abi", "canonical".to_string());
+    }
+    let kinds = instrumentation(module);
+    if !kinds.is_empty() {
+        hints.insert("instrumentation", kinds.join(","));
+    }
+
+    let backends = graphics_backends(module);
+    if !backends.is_empty() {
+        hints.insert("graphics_backend", backends.join(","));
+    }
+
+    // tells the modules without any toolchain markers apart from those there's just no detector for yet
+    if *language == Language::Unknown && module.component.is_none() {
+        hints.insert(
+            "toolchain_marker_absence",
+            format!("{:.1}", toolchain_marker_absence(module)),
+        );
+    }
+    if *language == Language::ObjectFile {
+        if let Some(source) = object_file_source_language(module) {
+            hints.insert("source_language", source.to_string());
+        }
+    }
+    if *language == Language::Emscripten {
+        if is_emscripten_pthread_worker(module) {
+            hints.insert("pthread", "worker".to_string());
+        }
+        if let Some(mode) = emscripten_output_mode(module) {
+            hints.insert("output_mode", mode.to_string());
+        }
+        if is_qt(module) {
+            hints.insert("framework", "qt".to_string());
+        }
+        if let Some(startup) = c_startup(module) {
+            hints.insert("startup", startup.to_string());
+        }
+        if let Some((_, source)) = dwarf_source_language(module) {
+            hints.insert("source_language", source.to_string());
+        } else if is_embind(module) {
+            hints.insert("source_language", "cpp".to_string());
+        }
+        if exposes_c_api_to_js(module) {
+            hints.insert("interop", "ccall".to_string());
+        }
+        if let Some(version) = emscripten_gl_version(module) {
+            hints.insert("uses_webgl", version.to_string());
+        }
+        if let Some(fs) = emscripten_fs(module) {
+            hints.insert("uses_emscripten_fs", fs.to_string());
+        }
+        if let Some(approach) = emscripten_exception_handling(module) {
+            hints.insert("exception_handling", approach.to_string());
+        }
+    }
+    if *language == Language::Rust {
+        if let Some(framework) = rust_framework(module) {
+            hints.insert("framework", framework.to_string());
+        }
+        let crates = rust_crates(module);
+        if !crates.is_empty() {
+            hints.insert("crates", crates.join(","));
+        }
+        if let Some(role) = bindgen_role(module) {
+            hints.insert("bindgen_role", role.to_string());
+        }
+        if let Some(abi) = bindgen_abi(module) {
+            hints.insert("bindgen_abi", abi.to_string());
+        }
+        if uses_bindgen_futures(module) {
+            hints.insert("uses_async", "wasm-bindgen-futures".to_string());
+        }
+        hints.insert("target", rust_target(module).to_string());
+        hints.insert("panic_strategy", rust_panic_strategy(module).to_string());
+    }
+    if *language == Language::Go {
+        if let Some(target) = go_target(module) {
+            hints.insert("target", target.to_string());
+        }
+        if let Some(era) = go_era(module) {
+            hints.insert("go_era", era.to_string());
+        }
+    }
+    if *language == Language::AssemblyScript {
+        // AssemblyScript borrows TypeScript's syntax, so TypeScript is where people tend to expect to see these
+        // modules - but TypeScript itself has no wasm compiler, so it is never a classification in its own right
+        hints.insert("syntax", "typescript".to_string());
+        if let Some(variant) = assemblyscript_runtime_variant(module) {
+            hints.insert("runtime_variant", variant.to_string());
+        }
+        if exports_assemblyscript_arguments_glue(module) {
+            hints.insert("interop", "bindings".to_string());
+        }
+    }
+    if *language == Language::Swift {
+        if let Some(environment) = swift_environment(module) {
+            hints.insert("environment", environment.to_string());
+        }
+    }
+    if *language == Language::Dart && is_flutter(module) {
+        hints.insert("framework", "flutter".to_string());
+    }
+    if *language == Language::Blazor {
+        let dotnet_language = if is_blazor_fsharp(module) {
+            "fsharp"
+        } else {
+            "csharp"
+        };
+        hints.insert("dotnet", dotnet_language.to_string());
+        if let Some(globalization) = blazor_globalization(module) {
+            hints.insert("globalization", globalization.to_string());
+        }
+    }
+
+    hints
+}
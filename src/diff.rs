@@ -0,0 +1,135 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use wasm_lang_inference::json::Json;
+use wasm_lang_inference::tally;
+
+struct Entry {
+    path: String,
+    language: String,
+}
+
+// Reads the results in the output of --format json or ndjson, each with its content hash. Scans from before the hash
+// was recorded fall back to the file name, which the downloader derives from the same hash.
+pub fn read_results(path: &str) -> Result<Vec<(String, Json)>> {
+    let text = fs::read_to_string(path).with_context(|| format!("failed to read {path}"))?;
+    let results: Vec<Json> = if text.trim_start().starts_with('[') {
+        match text.parse()? {
+            Json::Array(items) => items,
+            _ => unreachable!(),
+        }
+    } else {
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<_>>()?
+    };
+
+    results
+        .into_iter()
+        .map(|result| {
+            let file = field(path, &result, "path")?;
+            let hash = match result.get("hash").and_then(Json::as_str) {
+                Some(hash) => hash.to_string(),
+                None => Path::new(file)
+                    .file_stem()
+                    .map_or(file.into(), |stem| stem.to_string_lossy().into_owned()),
+            };
+            field(path, &result, "language")?;
+            Ok((hash, result))
+        })
+        .collect()
+}
+
+// A string field that every result has
+pub fn field<'a>(path: &str, result: &'a Json, key: &str) -> Result<&'a str> {
+    result
+        .get(key)
+        .and_then(Json::as_str)
+        .ok_or_else(|| anyhow!("{path}: result without a '{key}'"))
+}
+
+// The results of a scan by content hash, the last of them where a module was scanned more than once
+fn read_scan(path: &str) -> Result<BTreeMap<String, Entry>> {
+    let mut entries = BTreeMap::new();
+    for (hash, result) in read_results(path)? {
+        let entry = Entry {
+            path: field(path, &result, "path")?.to_string(),
+            language: field(path, &result, "language")?.to_string(),
+        };
+        entries.insert(hash, entry);
+    }
+    Ok(entries)
+}
+
+// Compares two scans, e.g. from successive monthly crawls - how the language counts moved, and which modules appeared,
+// disappeared or (after a heuristic change) were classified differently
+pub fn diff(args: &[String]) -> Result<()> {
+    let (old, new) = match args {
+        [old, new] => (read_scan(old)?, read_scan(new)?),
+        _ => bail!("usage: wasm-lang-inference --diff <old.json> <new.json>"),
+    };
+
+    let old_counts = tally(old.values().map(|e| e.language.as_str()));
+    let new_counts = tally(new.values().map(|e| e.language.as_str()));
+    let mut languages: Vec<&str> = old_counts
+        .keys()
+        .chain(new_counts.keys())
+        .copied()
+        .collect();
+    languages.sort();
+    languages.dedup();
+    let count =
+        |counts: &BTreeMap<&str, usize>, language: &str| *counts.get(language).unwrap_or(&0) as i64;
+    // biggest movers first
+    languages.sort_by_key(|language| {
+        Reverse((count(&new_counts, language) - count(&old_counts, language)).abs())
+    });
+
+    println!("{} -> {} modules", old.len(), new.len());
+    println!();
+    println!("languages:");
+    for language in languages {
+        let (before, after) = (count(&old_counts, language), count(&new_counts, language));
+        println!("  {language}: {before} -> {after} ({:+})", after - before);
+    }
+
+    let added: Vec<_> = new
+        .iter()
+        .filter(|(hash, _)| !old.contains_key(*hash))
+        .collect();
+    let removed: Vec<_> = old
+        .iter()
+        .filter(|(hash, _)| !new.contains_key(*hash))
+        .collect();
+    let reclassified: Vec<_> = new
+        .iter()
+        .filter_map(|(hash, entry)| {
+            old.get(hash)
+                .filter(|before| before.language != entry.language)
+                .map(|before| (entry, before))
+        })
+        .collect();
+
+    println!();
+    println!("added ({}):", added.len());
+    for (hash, entry) in added {
+        println!("  {hash} {} {}", entry.language, entry.path);
+    }
+    println!();
+    println!("removed ({}):", removed.len());
+    for (hash, entry) in removed {
+        println!("  {hash} {} {}", entry.language, entry.path);
+    }
+    if !reclassified.is_empty() {
+        println!();
+        println!("reclassified ({}):", reclassified.len());
+        for (entry, before) in reclassified {
+            println!("  {} {} -> {}", entry.path, before.language, entry.language);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,349 @@
+// Just enough of DWARF to read the attributes of a module's first compilation unit, which name the compiler and source
+// language it was built from. LLVM writes DWARF 4 or 5 into `.debug_*` custom sections for debug builds, e.g.
+// Emscripten's `-g`, Rust's dev profile and Swift's debug configuration.
+use std::str;
+
+// The custom sections the compilation unit is read from - any of them may be missing
+#[derive(Default)]
+pub(crate) struct DwarfSections<'a> {
+    pub info: &'a [u8],
+    pub abbrev: &'a [u8],
+    pub str: &'a [u8],
+    pub line_str: &'a [u8],
+    pub str_offsets: &'a [u8],
+}
+
+impl<'a> DwarfSections<'a> {
+    // Keeps the custom section if it is one of those above
+    pub fn add(&mut self, name: &str, data: &'a [u8]) {
+        match name {
+            ".debug_info" => self.info = data,
+            ".debug_abbrev" => self.abbrev = data,
+            ".debug_str" => self.str = data,
+            ".debug_line_str" => self.line_str = data,
+            ".debug_str_offsets" => self.str_offsets = data,
+            _ => {}
+        }
+    }
+}
+
+// The first compilation unit in `.debug_info`. A linked module has one per object file, but the first is normally the
+// one the linker was given first - the program itself, rather than the libraries linked in after it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompileUnit<'a> {
+    // the DWARF version, 2 - 5
+    pub version: u16,
+    // the DW_AT_language constant, e.g. 0x1c for Rust - see https://dwarfstd.org/languages.html
+    pub language: Option<u16>,
+    // the DW_AT_producer, e.g. `clang version 17.0.0 (...)`
+    pub producer: Option<&'a str>,
+}
+
+const DW_TAG_COMPILE_UNIT: u64 = 0x11;
+const DW_TAG_PARTIAL_UNIT: u64 = 0x3c;
+const DW_TAG_SKELETON_UNIT: u64 = 0x4a;
+
+const DW_AT_PRODUCER: u64 = 0x25;
+const DW_AT_LANGUAGE: u64 = 0x13;
+const DW_AT_STR_OFFSETS_BASE: u64 = 0x72;
+
+const DW_UT_SKELETON: u8 = 4;
+const DW_UT_SPLIT_COMPILE: u8 = 5;
+
+const DW_FORM_IMPLICIT_CONST: u64 = 0x21;
+const DW_FORM_INDIRECT: u64 = 0x16;
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8], position: usize) -> Reader<'a> {
+        Reader { data, position }
+    }
+
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self
+            .data
+            .get(self.position..self.position.checked_add(len)?)?;
+        self.position += len;
+        Some(bytes)
+    }
+
+    // an unsigned little-endian integer of 1 - 8 bytes
+    fn fixed(&mut self, len: usize) -> Option<u64> {
+        let bytes = self.bytes(len)?;
+        Some(
+            bytes
+                .iter()
+                .rev()
+                .fold(0, |value, byte| value << 8 | u64::from(*byte)),
+        )
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|bytes| bytes[0])
+    }
+
+    fn uleb(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn sleb(&mut self) -> Option<i64> {
+        let mut value = 0i64;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                value |= i64::from(byte & 0x7f) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                if shift < 64 && byte & 0x40 != 0 {
+                    value |= -1 << shift;
+                }
+                return Some(value);
+            }
+        }
+    }
+
+    fn skip(&mut self, len: u64) -> Option<()> {
+        self.bytes(usize::try_from(len).ok()?).map(|_| ())
+    }
+
+    // a nul-terminated string, as in `.debug_str`
+    fn string(&mut self) -> Option<&'a str> {
+        let rest = self.data.get(self.position..)?;
+        let len = rest.iter().position(|b| *b == 0)?;
+        self.position += len + 1;
+        str::from_utf8(&rest[..len]).ok()
+    }
+}
+
+fn string_at(section: &[u8], offset: u64) -> Option<&str> {
+    Reader::new(section, usize::try_from(offset).ok()?).string()
+}
+
+// An attribute value, as far as it matters here: a number, a string, or an index into `.debug_str_offsets`
+enum Value<'a> {
+    Number(u64),
+    String(&'a str),
+    StringIndex(u64),
+    Other,
+}
+
+struct Unit {
+    version: u16,
+    // 4 for 32-bit DWARF, 8 for 64-bit
+    offset_size: usize,
+    address_size: usize,
+}
+
+fn read_value<'a>(
+    reader: &mut Reader<'a>,
+    form: u64,
+    implicit_const: i64,
+    unit: &Unit,
+    sections: &DwarfSections<'a>,
+) -> Option<Value<'a>> {
+    let offset_size = unit.offset_size;
+    Some(match form {
+        // addr
+        0x01 => Value::Number(reader.fixed(unit.address_size)?),
+        // block2, block4, block, block1, exprloc
+        0x03 => {
+            let len = reader.fixed(2)?;
+            reader.skip(len)?;
+            Value::Other
+        }
+        0x04 => {
+            let len = reader.fixed(4)?;
+            reader.skip(len)?;
+            Value::Other
+        }
+        0x09 | 0x18 => {
+            let len = reader.uleb()?;
+            reader.skip(len)?;
+            Value::Other
+        }
+        0x0a => {
+            let len = reader.fixed(1)?;
+            reader.skip(len)?;
+            Value::Other
+        }
+        // data1, data2, data4, data8, flag, ref1, ref2, ref4, ref8, ref_sig8, ref_sup4, ref_sup8
+        0x0b | 0x0c | 0x11 => Value::Number(reader.fixed(1)?),
+        0x05 | 0x12 => Value::Number(reader.fixed(2)?),
+        0x06 | 0x13 | 0x1c => Value::Number(reader.fixed(4)?),
+        0x07 | 0x14 | 0x20 | 0x24 => Value::Number(reader.fixed(8)?),
+        // data16
+        0x1e => {
+            reader.skip(16)?;
+            Value::Other
+        }
+        // sdata
+        0x0d => Value::Number(reader.sleb()? as u64),
+        // udata, ref_udata, addrx, loclistx, rnglistx, GNU_addr_index
+        0x0f | 0x15 | 0x1b | 0x22 | 0x23 | 0x1f01 => Value::Number(reader.uleb()?),
+        // string
+        0x08 => Value::String(reader.string()?),
+        // strp, line_strp
+        0x0e => Value::String(string_at(sections.str, reader.fixed(offset_size)?)?),
+        0x1f => Value::String(string_at(sections.line_str, reader.fixed(offset_size)?)?),
+        // ref_addr, an address in DWARF 2 and an offset since
+        0x10 if unit.version == 2 => Value::Number(reader.fixed(unit.address_size)?),
+        // ref_addr, sec_offset, strp_sup, GNU_ref_alt, GNU_strp_alt
+        0x10 | 0x17 | 0x1d | 0x1f20 | 0x1f21 => Value::Number(reader.fixed(offset_size)?),
+        // flag_present
+        0x19 => Value::Number(1),
+        DW_FORM_IMPLICIT_CONST => Value::Number(implicit_const as u64),
+        // strx, GNU_str_index, strx1 - strx4
+        0x1a | 0x1f02 => Value::StringIndex(reader.uleb()?),
+        0x25 => Value::StringIndex(reader.fixed(1)?),
+        0x26 => Value::StringIndex(reader.fixed(2)?),
+        0x27 => Value::StringIndex(reader.fixed(3)?),
+        0x28 => Value::StringIndex(reader.fixed(4)?),
+        // addrx1 - addrx4
+        0x29 => Value::Number(reader.fixed(1)?),
+        0x2a => Value::Number(reader.fixed(2)?),
+        0x2b => Value::Number(reader.fixed(3)?),
+        0x2c => Value::Number(reader.fixed(4)?),
+        DW_FORM_INDIRECT => {
+            let form = reader.uleb()?;
+            if form == DW_FORM_INDIRECT {
+                return None;
+            }
+            return read_value(reader, form, implicit_const, unit, sections);
+        }
+        // there's no telling how long a value of an unknown form is, so nothing after it can be read
+        _ => return None,
+    })
+}
+
+struct AttributeSpec {
+    attribute: u64,
+    form: u64,
+    // the value itself, for DW_FORM_implicit_const, which is stored in the abbreviation rather than the entry
+    implicit_const: i64,
+}
+
+// The tag and attribute specifications of the abbreviation with the given code
+fn find_abbreviation(abbrev: &[u8], offset: u64, code: u64) -> Option<(u64, Vec<AttributeSpec>)> {
+    let mut reader = Reader::new(abbrev, usize::try_from(offset).ok()?);
+    loop {
+        let entry_code = reader.uleb()?;
+        if entry_code == 0 {
+            return None;
+        }
+        let tag = reader.uleb()?;
+        // whether the entry has children
+        reader.u8()?;
+        let mut specs = vec![];
+        loop {
+            let attribute = reader.uleb()?;
+            let form = reader.uleb()?;
+            if attribute == 0 && form == 0 {
+                break;
+            }
+            let implicit_const = if form == DW_FORM_IMPLICIT_CONST {
+                reader.sleb()?
+            } else {
+                0
+            };
+            specs.push(AttributeSpec {
+                attribute,
+                form,
+                implicit_const,
+            });
+        }
+        if entry_code == code {
+            return Some((tag, specs));
+        }
+    }
+}
+
+// The first compilation unit, if the module has one that can be read. Only its header and first entry are read,
+// not the rest of the unit, let alone the other units.
+pub(crate) fn read_compile_unit<'a>(sections: &DwarfSections<'a>) -> Option<CompileUnit<'a>> {
+    let mut reader = Reader::new(sections.info, 0);
+    let (offset_size, _unit_length) = match reader.fixed(4)? {
+        0xffff_ffff => (8, reader.fixed(8)?),
+        length => (4, length),
+    };
+    let version = reader.fixed(2)? as u16;
+    let (address_size, abbrev_offset) = match version {
+        2..=4 => {
+            let abbrev_offset = reader.fixed(offset_size)?;
+            (reader.u8()?, abbrev_offset)
+        }
+        5 => {
+            let unit_type = reader.u8()?;
+            let address_size = reader.u8()?;
+            let abbrev_offset = reader.fixed(offset_size)?;
+            if unit_type == DW_UT_SKELETON || unit_type == DW_UT_SPLIT_COMPILE {
+                // the dwo_id
+                reader.skip(8)?;
+            }
+            (address_size, abbrev_offset)
+        }
+        _ => return None,
+    };
+    let unit = Unit {
+        version,
+        offset_size,
+        address_size: usize::from(address_size),
+    };
+
+    let code = reader.uleb()?;
+    let (tag, specs) = find_abbreviation(sections.abbrev, abbrev_offset, code)?;
+    if ![
+        DW_TAG_COMPILE_UNIT,
+        DW_TAG_PARTIAL_UNIT,
+        DW_TAG_SKELETON_UNIT,
+    ]
+    .contains(&tag)
+    {
+        return None;
+    }
+    let mut language = None;
+    let mut producer = None;
+    // strx forms index the unit's slice of `.debug_str_offsets`, which is found from an attribute that may come
+    // after the producer, so they are resolved once all the attributes are read. It starts after an 8-byte header
+    // (16-byte for 64-bit DWARF) when the attribute is missing.
+    let mut producer_index = None;
+    let mut str_offsets_base = 2 * offset_size as u64;
+    for spec in specs {
+        let value = read_value(&mut reader, spec.form, spec.implicit_const, &unit, sections)?;
+        match (spec.attribute, value) {
+            (DW_AT_LANGUAGE, Value::Number(n)) => language = u16::try_from(n).ok(),
+            (DW_AT_PRODUCER, Value::String(s)) => producer = Some(s),
+            (DW_AT_PRODUCER, Value::StringIndex(index)) => producer_index = Some(index),
+            (DW_AT_STR_OFFSETS_BASE, Value::Number(n)) => str_offsets_base = n,
+            _ => {}
+        }
+    }
+    if let Some(index) = producer_index {
+        let entry = index
+            .checked_mul(offset_size as u64)
+            .and_then(|offset| offset.checked_add(str_offsets_base))
+            .and_then(|offset| usize::try_from(offset).ok());
+        producer = entry
+            .and_then(|entry| Reader::new(sections.str_offsets, entry).fixed(offset_size))
+            .and_then(|offset| string_at(sections.str, offset));
+    }
+
+    Some(CompileUnit {
+        version,
+        language,
+        producer,
+    })
+}
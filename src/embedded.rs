@@ -0,0 +1,84 @@
+use crate::is_component;
+use crate::parse::WASM_MAGIC;
+use wasmparser::{Parser, Payload};
+
+// Some crawled "wasm" files are really JS that carries the module as a base64 string, e.g. a data URI passed to
+// WebAssembly.instantiate, or the single-file output of Emscripten (-sSINGLE_FILE). The module itself can be
+// recovered from the first base64 run that decodes to the wasm magic number.
+
+// "\0asm" encodes to "AGFzbQ", whatever follows it
+const ENCODED_MAGIC: &[u8] = b"AGFzbQ";
+
+fn is_base64(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'+' || c == b'/'
+}
+
+fn base64_value(c: u8) -> u8 {
+    match c {
+        b'A'..=b'Z' => c - b'A',
+        b'a'..=b'z' => c - b'a' + 26,
+        b'0'..=b'9' => c - b'0' + 52,
+        b'+' => 62,
+        _ => 63,
+    }
+}
+
+fn decode_base64(encoded: &[u8]) -> Vec<u8> {
+    let mut decoded = Vec::with_capacity(encoded.len() * 3 / 4);
+    for chunk in encoded.chunks(4) {
+        let mut bits = 0u32;
+        for (i, c) in chunk.iter().enumerate() {
+            bits |= (base64_value(*c) as u32) << (18 - 6 * i);
+        }
+        let bytes = bits.to_be_bytes();
+        // a trailing partial chunk of n characters holds n - 1 bytes
+        decoded.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+    decoded
+}
+
+pub fn extract_embedded(buf: &[u8]) -> Option<Vec<u8>> {
+    let start = buf
+        .windows(ENCODED_MAGIC.len())
+        .position(|w| w == ENCODED_MAGIC)?;
+    let len = buf[start..].iter().take_while(|c| is_base64(**c)).count();
+    Some(decode_base64(&buf[start..start + len]))
+}
+
+// Some modules carry a whole second module in a custom section, e.g. a fallback build for engines without a feature
+// the main build relies on, or a bundler's payload. A section whose contents start with the magic number holds one.
+// Each could hold another in turn, so they are searched to a limited depth, which is far more than any real packaging
+// needs, but stops a crafted module from nesting the search out of stack.
+const MAX_EMBEDDING_DEPTH: usize = 8;
+
+// The core modules embedded in custom sections, and those embedded in them, each identified by the path of sections
+// it is in, e.g. `#custom[fallback]#custom[inner]`. A module that can't be read in full gives those found up to then.
+pub fn custom_section_modules(buf: &[u8]) -> Vec<(String, &[u8])> {
+    let mut modules = vec![];
+    read_custom_section_modules(buf, "", 1, &mut modules);
+    modules
+}
+
+fn read_custom_section_modules<'a>(
+    buf: &'a [u8],
+    path: &str,
+    depth: usize,
+    modules: &mut Vec<(String, &'a [u8])>,
+) {
+    if depth > MAX_EMBEDDING_DEPTH || !buf.starts_with(WASM_MAGIC) || is_component(buf) {
+        return;
+    }
+    for payload in Parser::new(0).parse_all(buf) {
+        match payload {
+            Ok(Payload::CustomSection { name, data, .. })
+                if data.starts_with(WASM_MAGIC) && !is_component(data) =>
+            {
+                let path = format!("{path}#custom[{name}]");
+                modules.push((path.clone(), data));
+                read_custom_section_modules(data, &path, depth + 1, modules);
+            }
+            Ok(_) => {}
+            Err(_) => break,
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use wasm_lang_inference::{classify, parse_wasm_with, Language, ParseOptions};
+
+// a rule's measured precision has to be this far from its confidence, over at least this many modules, before it is
+// worth changing
+const CONFIDENCE_TOLERANCE: f32 = 0.1;
+const MIN_MATCHES: usize = 20;
+
+// Measures the heuristics against a hand-labelled set of modules, given as a CSV of `path,true_language` rows. Prints
+// a confusion matrix, precision / recall per language, and how often each rule is right compared to the confidence it
+// claims, so that changes to the rules can be judged by numbers rather than by eye.
+pub fn evaluate(args: &[String]) -> Result<()> {
+    let path = match args {
+        [path] => path,
+        _ => bail!("usage: wasm-lang-inference evaluate <labels.csv>"),
+    };
+    let labels = fs::read_to_string(path).with_context(|| format!("failed to read {path}"))?;
+
+    // (true, inferred) -> count
+    let mut confusion: BTreeMap<(Language, Language), usize> = BTreeMap::new();
+    // rule id -> (confidence, matched, correct)
+    let mut rules: BTreeMap<&str, (f32, usize, usize)> = BTreeMap::new();
+    for (line_number, line) in labels.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (line_number == 0 && line.starts_with("path,")) {
+            continue;
+        }
+        let (module_path, label) = match line.rsplit_once(',') {
+            Some(row) => row,
+            None => bail!("{path}:{}: expected path,true_language", line_number + 1),
+        };
+        let truth: Language = label
+            .trim()
+            .parse()
+            .with_context(|| format!("{path}:{}", line_number + 1))?;
+        let buf = fs::read(module_path).with_context(|| format!("failed to read {module_path}"))?;
+        let classification = classify(&parse_wasm_with(
+            &buf,
+            &ParseOptions {
+                scan_code: true,
+                ..ParseOptions::default()
+            },
+        ));
+
+        *confusion
+            .entry((truth, classification.language))
+            .or_insert(0) += 1;
+        if let Some(rule) = classification.rule {
+            let entry = rules.entry(rule.id).or_insert((rule.confidence, 0, 0));
+            entry.1 += 1;
+            if rule.language == truth {
+                entry.2 += 1;
+            }
+        }
+    }
+
+    let total: usize = confusion.values().sum();
+    if total == 0 {
+        bail!("{path} doesn't list any modules");
+    }
+    let mut languages: Vec<Language> = confusion.keys().flat_map(|(t, i)| [*t, *i]).collect();
+    languages.sort();
+    languages.dedup();
+    let count =
+        |truth: Language, inferred: Language| *confusion.get(&(truth, inferred)).unwrap_or(&0);
+
+    println!("confusion matrix (rows are the true language, columns the inferred one):");
+    let width = languages
+        .iter()
+        .map(|l| l.name().len())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+    let header: Vec<&str> = languages.iter().map(|l| l.name()).collect();
+    println!("{:width$}  {}", "", header.join(" "));
+    for truth in &languages {
+        let cells: Vec<String> = languages
+            .iter()
+            .map(|inferred| format!("{:>1$}", count(*truth, *inferred), inferred.name().len()))
+            .collect();
+        println!("{:width$}  {}", truth.name(), cells.join(" "));
+    }
+
+    println!();
+    println!("per language:");
+    for language in &languages {
+        let correct = count(*language, *language);
+        let inferred: usize = languages.iter().map(|t| count(*t, *language)).sum();
+        let actual: usize = languages.iter().map(|i| count(*language, *i)).sum();
+        let percent = |n: usize, of: usize| {
+            if of == 0 {
+                "-".to_string()
+            } else {
+                format!("{:.0}%", n as f64 * 100.0 / of as f64)
+            }
+        };
+        println!(
+            "  {:width$}  precision {:>4}  recall {:>4}  ({actual} labelled, {inferred} inferred)",
+            language.name(),
+            percent(correct, inferred),
+            percent(correct, actual),
+        );
+    }
+    let correct: usize = languages.iter().map(|l| count(*l, *l)).sum();
+    println!(
+        "accuracy: {:.0}% of {total} modules",
+        correct as f64 * 100.0 / total as f64
+    );
+
+    println!();
+    println!("rules:");
+    for (id, (confidence, matched, correct)) in &rules {
+        let precision = *correct as f32 / *matched as f32;
+        let suggestion =
+            if *matched >= MIN_MATCHES && (precision - confidence).abs() > CONFIDENCE_TOLERANCE {
+                format!(", consider a confidence of {precision:.2}")
+            } else {
+                String::new()
+            };
+        println!(
+            "  {id}: {correct} of {matched} correct ({:.0}%), confidence {confidence}{suggestion}",
+            precision * 100.0
+        );
+    }
+
+    Ok(())
+}
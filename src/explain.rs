@@ -0,0 +1,80 @@
+use anyhow::{bail, Result};
+use wasm_lang_inference::{Language, Tier, RULES};
+
+// Documents the rules that lead to a language, independent of any particular module - what each one checks, where it
+// sits in the chain, and the real-world modules that motivated it
+pub fn explain(args: &[String]) -> Result<()> {
+    let language: Language = match args {
+        [language] => language.parse()?,
+        _ => bail!("usage: wasm-lang-inference explain <language>"),
+    };
+
+    let rules: Vec<_> = RULES
+        .iter()
+        .enumerate()
+        .filter(|(_, rule)| rule.language == language)
+        .collect();
+    match language {
+        Language::Unknown => {
+            println!(
+                "unknown: the module didn't match any of the {} rules",
+                RULES.len()
+            );
+            return Ok(());
+        }
+        Language::Empty => {
+            println!(
+                "empty: the file is too short to hold the wasm header, so isn't parsed at all"
+            );
+            return Ok(());
+        }
+        Language::ObjectFile => {
+            println!("object_file: the module has a `linking` section, which the linker removes from finished modules");
+            return Ok(());
+        }
+        Language::Coredump => {
+            println!(
+                "coredump: the module has a `core` section, so is the state of a crashed program"
+            );
+            return Ok(());
+        }
+        Language::DebugInfo => {
+            println!(
+                "debug_info: the module defines no functions, and is mostly `.debug_*` DWARF sections"
+            );
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    println!(
+        "{language}: {} of the {} rules, which are tried in priority order until one matches",
+        rules.len(),
+        RULES.len()
+    );
+    for (index, rule) in rules {
+        println!();
+        println!(
+            "#{} {} (confidence {}{}{})",
+            index + 1,
+            rule.id,
+            rule.confidence,
+            if rule.tier == Tier::Weak {
+                ", lenient mode only"
+            } else {
+                ""
+            },
+            if rule.whole_module {
+                ", needs the whole module"
+            } else {
+                ""
+            }
+        );
+        println!("  {}", rule.description);
+        for note in rule.notes {
+            println!("  - {note}");
+        }
+    }
+
+    Ok(())
+}
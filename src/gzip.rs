@@ -0,0 +1,251 @@
+// A small gzip / DEFLATE (RFC 1952 / 1951) decoder, for modules that were saved with the compression the server sent
+// them with. Only decompression is needed, and only well-formed input, so any error just returns None. The trailing
+// CRC isn't checked - a corrupt module will fail to parse anyway.
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+// DEFLATE compresses runs of up to 1032:1, so a small file can expand to gigabytes. Anything that would be larger than
+// this once decompressed isn't treated as a module, the same limit the server puts on the bodies it accepts.
+pub const MAX_OUTPUT: usize = 64 * 1024 * 1024;
+
+pub fn is_gzip(buf: &[u8]) -> bool {
+    buf.starts_with(GZIP_MAGIC)
+}
+
+pub fn gunzip(buf: &[u8]) -> Option<Vec<u8>> {
+    gunzip_limited(buf, MAX_OUTPUT)
+}
+
+// As gunzip, returning None once the output would exceed `max_output` bytes
+pub fn gunzip_limited(buf: &[u8], max_output: usize) -> Option<Vec<u8>> {
+    const FEXTRA: u8 = 4;
+    const FNAME: u8 = 8;
+    const FCOMMENT: u8 = 16;
+    const FHCRC: u8 = 2;
+
+    // magic, compression method (8 = deflate), flags, mtime, extra flags, OS
+    if !is_gzip(buf) || *buf.get(2)? != 8 {
+        return None;
+    }
+    let flags = *buf.get(3)?;
+    let mut pos = 10;
+    if flags & FEXTRA != 0 {
+        let len = u16::from_le_bytes([*buf.get(pos)?, *buf.get(pos + 1)?]) as usize;
+        pos += 2 + len;
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            pos += buf.get(pos..)?.iter().position(|&b| b == 0)? + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        pos += 2;
+    }
+    inflate(buf.get(pos..)?, max_output)
+}
+
+struct Bits<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    bit: u32,
+}
+
+impl Bits<'_> {
+    fn bit(&mut self) -> Option<u32> {
+        let byte = *self.buf.get(self.pos)?;
+        let bit = (byte >> self.bit) & 1;
+        self.bit += 1;
+        if self.bit == 8 {
+            self.bit = 0;
+            self.pos += 1;
+        }
+        Some(bit as u32)
+    }
+
+    // n bits, least significant first
+    fn bits(&mut self, n: u32) -> Option<u32> {
+        let mut value = 0;
+        for i in 0..n {
+            value |= self.bit()? << i;
+        }
+        Some(value)
+    }
+}
+
+// A canonical Huffman code, as the number of codes of each length and the symbols in code order
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Huffman {
+        let mut counts = [0; 16];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+        let mut symbols: Vec<(u8, u16)> = lengths
+            .iter()
+            .enumerate()
+            .filter(|(_, &length)| length > 0)
+            .map(|(symbol, &length)| (length, symbol as u16))
+            .collect();
+        symbols.sort();
+        Huffman {
+            counts,
+            symbols: symbols.into_iter().map(|(_, symbol)| symbol).collect(),
+        }
+    }
+
+    fn decode(&self, bits: &mut Bits) -> Option<u16> {
+        // codes are read a bit at a time from the most significant end, with the first code of each length following
+        // on from the last code of the length before
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for length in 1..16 {
+            code |= bits.bit()? as i32;
+            let count = self.counts[length] as i32;
+            if code - first < count {
+                return self.symbols.get((index + code - first) as usize).copied();
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        None
+    }
+}
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+// the order the code length code lengths are given in a dynamic block
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+fn inflate(buf: &[u8], max_output: usize) -> Option<Vec<u8>> {
+    let mut bits = Bits {
+        buf,
+        pos: 0,
+        bit: 0,
+    };
+    let mut out = vec![];
+    loop {
+        let last = bits.bit()? == 1;
+        match bits.bits(2)? {
+            0 => {
+                // stored, starting at the next byte boundary
+                if bits.bit != 0 {
+                    bits.bit = 0;
+                    bits.pos += 1;
+                }
+                let header = buf.get(bits.pos..bits.pos + 4)?;
+                let len = u16::from_le_bytes([header[0], header[1]]) as usize;
+                bits.pos += 4;
+                if out.len() + len > max_output {
+                    return None;
+                }
+                out.extend_from_slice(buf.get(bits.pos..bits.pos + len)?);
+                bits.pos += len;
+            }
+            1 => {
+                let mut lengths = [0u8; 288];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..].fill(8);
+                inflate_block(
+                    &mut bits,
+                    &mut out,
+                    &Huffman::new(&lengths),
+                    &Huffman::new(&[5; 30]),
+                    max_output,
+                )?;
+            }
+            2 => {
+                let literals = bits.bits(5)? as usize + 257;
+                let distances = bits.bits(5)? as usize + 1;
+                let code_lengths = bits.bits(4)? as usize + 4;
+                let mut lengths = [0u8; 19];
+                for &index in &CODE_LENGTH_ORDER[..code_lengths] {
+                    lengths[index] = bits.bits(3)? as u8;
+                }
+                let code_length_code = Huffman::new(&lengths);
+
+                let mut lengths = vec![];
+                while lengths.len() < literals + distances {
+                    let (value, repeat) = match code_length_code.decode(&mut bits)? {
+                        symbol @ 0..=15 => (symbol as u8, 1),
+                        16 => (*lengths.last()?, 3 + bits.bits(2)?),
+                        17 => (0, 3 + bits.bits(3)?),
+                        18 => (0, 11 + bits.bits(7)?),
+                        _ => return None,
+                    };
+                    lengths.extend((0..repeat).map(|_| value));
+                }
+                if lengths.len() != literals + distances {
+                    return None;
+                }
+                let literal_code = Huffman::new(&lengths[..literals]);
+                let distance_code = Huffman::new(&lengths[literals..]);
+                inflate_block(
+                    &mut bits,
+                    &mut out,
+                    &literal_code,
+                    &distance_code,
+                    max_output,
+                )?;
+            }
+            _ => return None,
+        }
+        if last {
+            return Some(out);
+        }
+    }
+}
+
+fn inflate_block(
+    bits: &mut Bits,
+    out: &mut Vec<u8>,
+    literal_code: &Huffman,
+    distance_code: &Huffman,
+    max_output: usize,
+) -> Option<()> {
+    loop {
+        let symbol = literal_code.decode(bits)? as usize;
+        match symbol {
+            0..=255 if out.len() == max_output => return None,
+            0..=255 => out.push(symbol as u8),
+            256 => return Some(()),
+            _ => {
+                let index = symbol - 257;
+                let length = *LENGTH_BASE.get(index)? as usize
+                    + bits.bits(*LENGTH_EXTRA.get(index)? as u32)? as usize;
+                let index = distance_code.decode(bits)? as usize;
+                let distance = *DISTANCE_BASE.get(index)? as usize
+                    + bits.bits(*DISTANCE_EXTRA.get(index)? as u32)? as usize;
+                if distance > out.len() || out.len() + length > max_output {
+                    return None;
+                }
+                // the match can overlap the bytes it is copying, so it has to be copied a byte at a time
+                let start = out.len() - distance;
+                for i in 0..length {
+                    out.push(out[start + i]);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+mod classifier;
+mod component;
+mod detect;
+mod dwarf;
+mod embedded;
+pub mod gzip;
+pub mod hash;
+pub mod json;
+mod parse;
+#[cfg(feature = "plugins")]
+pub mod plugins;
+pub mod signatures;
+#[cfg(feature = "testing")]
+pub mod testing;
+
+pub use classifier::{Classifier, Summary};
+pub use component::{core_modules, is_component, ComponentInterfaces};
+pub use detect::{
+    infer_all_languages, infer_language, infer_language_prefix, infer_language_with_mode,
+    matching_rule, matching_rule_with_mode, matching_rules, Mode, Rule, Tier, RULES,
+};
+pub use dwarf::CompileUnit;
+pub use embedded::{custom_section_modules, extract_embedded};
+// the wasmparser types that WasmModule's fields are made of, so that inspecting them doesn't need a matching wasmparser
+// dependency
+pub use parse::{
+    min_feature_level, parse, parse_wasm, parse_wasm_with, DylinkInfo, NameInfo, ParseError,
+    ParseOptions, Producer, WasmModule, CODE_FEATURES, DEFAULT_MAX_SYMBOLS, FEATURES,
+};
+pub use wasmparser::{Export, ExternalKind, FuncType, Import, TableType, Type, TypeRef};
+
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Clone, Copy)]
+pub enum Language {
+    Rust,
+    Emscripten,
+    // TypeScript-flavoured, but there is deliberately no TypeScript variant - it can't be compiled to wasm as-is
+    AssemblyScript,
+    Blazor,
+    Unknown,
+    Go,
+    HandWritten,
+    Scheme,
+    Julia,
+    // zero-byte or truncated files, typically failed downloads, that are too short to even hold the wasm header
+    Empty,
+    // relocatable object files, which are an intermediate build artifact rather than a finished module
+    ObjectFile,
+    // companion files holding only the DWARF debug info split out of a module, with no code of their own
+    DebugInfo,
+    Swift,
+    Dart,
+    Java,
+    Elm,
+    Gren,
+    // crash dumps in the wasm coredump format, which capture a program's state rather than being a program
+    Coredump,
+}
+
+// A coarser grouping of languages, for reports where the individual languages are too fine-grained
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Clone, Copy)]
+pub enum LanguageFamily {
+    // C / C++ compiled with Emscripten or clang
+    CFamily,
+    // languages without a garbage collector or large runtime
+    Systems,
+    // languages that ship a garbage-collected runtime within the module
+    Managed,
+    Assembly,
+    Unknown,
+}
+
+impl Language {
+    pub const ALL: &'static [Language] = &[
+        Language::Rust,
+        Language::Emscripten,
+        Language::AssemblyScript,
+        Language::Blazor,
+        Language::Unknown,
+        Language::Go,
+        Language::HandWritten,
+        Language::Scheme,
+        Language::Julia,
+        Language::Empty,
+        Language::ObjectFile,
+        Language::DebugInfo,
+        Language::Swift,
+        Language::Dart,
+        Language::Java,
+        Language::Elm,
+        Language::Gren,
+        Language::Coredump,
+    ];
+
+    // The name used in machine-readable output, and parsed back by FromStr. People persist these (e.g. the JSON scans
+    // compared by --diff), so they are a stable contract: a name must never be changed or reused once released, and
+    // tests/language_names.rs pins the full list.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::Emscripten => "emscripten",
+            Language::AssemblyScript => "assemblyscript",
+            Language::Blazor => "blazor",
+            Language::Unknown => "unknown",
+            Language::Go => "go",
+            Language::HandWritten => "handwritten",
+            Language::Scheme => "scheme",
+            Language::Julia => "julia",
+            Language::Empty => "empty",
+            Language::ObjectFile => "object_file",
+            Language::DebugInfo => "debug_info",
+            Language::Swift => "swift",
+            Language::Dart => "dart",
+            Language::Java => "java",
+            Language::Elm => "elm",
+            Language::Gren => "gren",
+            Language::Coredump => "coredump",
+        }
+    }
+
+    pub fn family(&self) -> LanguageFamily {
+        match self {
+            Language::Emscripten => LanguageFamily::CFamily,
+            Language::Rust | Language::Swift => LanguageFamily::Systems,
+            Language::Go
+            | Language::Blazor
+            | Language::AssemblyScript
+            | Language::Scheme
+            | Language::Julia
+            | Language::Dart
+            | Language::Java
+            | Language::Elm
+            | Language::Gren => LanguageFamily::Managed,
+            Language::HandWritten => LanguageFamily::Assembly,
+            Language::Unknown
+            | Language::Empty
+            | Language::ObjectFile
+            | Language::DebugInfo
+            | Language::Coredump => LanguageFamily::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// Language names as they are compared when parsing: case-insensitively, and ignoring `_` / `-`, so that the names
+// from the text output (`ObjectFile`) are accepted as well as those from the JSON (`object_file`)
+fn normalise_language(s: &str) -> String {
+    s.chars()
+        .filter(|c| !matches!(c, '_' | '-'))
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+// the number of single character insertions, deletions or substitutions to turn one string into the other
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+// how many edits away a misspelling can be for `did you mean` to suggest the name
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+impl FromStr for Language {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Language> {
+        let normalised = normalise_language(s);
+        if let Some(language) = Language::ALL
+            .iter()
+            .find(|language| normalise_language(language.name()) == normalised)
+        {
+            return Ok(*language);
+        }
+
+        let names: Vec<&str> = Language::ALL.iter().map(Language::name).collect();
+        let closest = names
+            .iter()
+            .map(|name| (edit_distance(&normalised, &normalise_language(name)), *name))
+            .min();
+        match closest {
+            Some((distance, name)) if distance <= MAX_SUGGESTION_DISTANCE => Err(anyhow::anyhow!(
+                "unknown language '{s}', did you mean '{name}'? Expected one of: {}",
+                names.join(", ")
+            )),
+            _ => Err(anyhow::anyhow!(
+                "unknown language '{s}', expected one of: {}",
+                names.join(", ")
+            )),
+        }
+    }
+}
+
+// Additional detail about a module that doesn't change its language, e.g. which toolchain configuration produced it
+pub type Hints = BTreeMap<&'static str, String>;
+
+pub struct Classification {
+    pub language: Language,
+    pub hints: Hints,
+    // the rule that determined the language, None for Unknown modules
+    pub rule: Option<&'static Rule>,
+    // from the matching rule - 0.0 for Unknown modules, as there's nothing to go on
+    pub confidence: f32,
+    // the JS interop layer the module expects, e.g. wasm-bindgen, embind, javascriptkit or syscall/js
+    pub js_binding: Option<&'static str>,
+    // what it takes to run the module - standalone, wasi or needs-js-glue - or None for components, and for the
+    // modules that aren't run at all, such as object files
+    pub runnability: Option<&'static str>,
+}
+
+impl Classification {
+    // for the modules that are recognised by what they are rather than by a rule, such as empty modules and object
+    // files, which are certain
+    fn without_rule(language: Language, hints: Hints) -> Classification {
+        Classification {
+            language,
+            hints,
+            rule: None,
+            confidence: 1.0,
+            js_binding: None,
+            runnability: None,
+        }
+    }
+}
+
+pub fn classify(module: &WasmModule) -> Classification {
+    classify_with_mode(module, Mode::Lenient)
+}
+
+pub fn classify_with_mode(module: &WasmModule, mode: Mode) -> Classification {
+    if let Some(language) = detect::special_language(module) {
+        // object files still carry their toolchain's markers, the others have nothing to add
+        let hints = if language == Language::ObjectFile {
+            detect::infer_hints(module, &language)
+        } else {
+            Hints::new()
+        };
+        return Classification::without_rule(language, hints);
+    }
+
+    let rule = matching_rule_with_mode(module, mode);
+    let language = rule.map_or(Language::Unknown, |rule| rule.language);
+    let mut hints = detect::infer_hints(module, &language);
+    if let Some(languages) = rule.and_then(|rule| detect::polyglot_languages(module, rule)) {
+        let names: Vec<&str> = languages.iter().map(Language::name).collect();
+        hints.insert("polyglot", names.join(","));
+    }
+    Classification {
+        language,
+        hints,
+        rule,
+        confidence: rule.map_or(0.0, |rule| rule.confidence_for(module)),
+        js_binding: detect::infer_js_binding(module),
+        runnability: detect::infer_runnability(module),
+    }
+}
+
+pub fn format_hints(hints: &Hints) -> String {
+    hints
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Counts of each distinct item, e.g. modules per language, ordered so that output is stable from run to run
+pub fn tally<K: Ord, I: IntoIterator<Item = K>>(items: I) -> BTreeMap<K, usize> {
+    let mut counts = BTreeMap::new();
+    for item in items {
+        *counts.entry(item).or_insert(0) += 1;
+    }
+    counts
+}
@@ -1,167 +1,1894 @@
-use std::collections::HashMap;
+mod analyze;
+mod baseline;
+mod calibrate;
+mod checkpoint;
+mod color;
+mod diff;
+mod evaluate;
+mod explain;
+mod merge;
+mod sarif;
+mod schema;
+#[cfg(feature = "server")]
+mod serve;
+mod wast;
+mod watch;
+mod workers;
+
+use anyhow::{anyhow, bail, Context, Result};
+use checkpoint::Checkpoint;
+use color::ColorChoice;
+use std::borrow::Borrow;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
 use std::fs;
-use wasmparser::{Export, Import, Parser, Payload};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use wasm_lang_inference::gzip;
+use wasm_lang_inference::hash::{sha256, sha256_hex};
+use wasm_lang_inference::json::Json;
+#[cfg(feature = "plugins")]
+use wasm_lang_inference::plugins::Plugins;
+use wasm_lang_inference::signatures::Signatures;
+use wasm_lang_inference::{
+    classify_with_mode, core_modules, custom_section_modules, extract_embedded, format_hints,
+    is_component, matching_rules, min_feature_level, parse_wasm_with, tally, Classification,
+    Language, Mode, ParseError, ParseOptions, WasmModule, CODE_FEATURES, DEFAULT_MAX_SYMBOLS,
+    FEATURES,
+};
 
-#[derive(Eq, PartialEq, Hash, Debug)]
-enum Language {
-    Rust,
-    Emscripten,
-    AssemblyScript,
-    Blazor,
-    Unknown,
-    Go,
+#[derive(PartialEq)]
+enum SortOrder {
+    Size,
+    Language,
 }
 
-struct WasmModule<'a> {
-    imports: Vec<Import<'a>>,
-    exports: Vec<Export<'a>>,
+#[derive(PartialEq)]
+enum GroupBy {
+    Language,
+    Family,
+    // by the toolchain hint, what compiled each module rather than what it was written in
+    Toolchain,
 }
 
-impl WasmModule<'_> {
-    fn any_imports_match<F: Fn(&Import) -> bool>(&self, f: F) -> bool {
-        self.imports.iter().any(|i| f(i))
+// A toolchain name, as it's printed in the summary - unquoted, like the languages and families
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Toolchain<'a>(&'a str);
+
+impl std::fmt::Debug for Toolchain<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.0)
     }
+}
 
-    fn any_exports_match<F: Fn(&Export) -> bool>(&self, f: F) -> bool {
-        self.exports.iter().any(|i| f(i))
+impl<'a> Toolchain<'a> {
+    fn of(classification: &'a Classification) -> Self {
+        Toolchain(
+            classification
+                .hints
+                .get("toolchain")
+                .map_or("unknown", String::as_str),
+        )
     }
 }
 
-fn parse_wasm(buf: &Vec<u8>) -> WasmModule {
-    let mut imports = vec![];
-    let mut exports = vec![];
+// which module to pick, by size, as the example of each language
+#[derive(PartialEq, Clone, Copy)]
+enum Example {
+    Largest,
+    Smallest,
+    Median,
+}
 
-    for payload in Parser::new(0).parse_all(buf) {
-        match payload.unwrap() {
-            Payload::ImportSection(s) => {
-                for import in s {
-                    imports.push(import.unwrap());
-                }
+#[derive(PartialEq)]
+enum Format {
+    Text,
+    // a single JSON array of per-file results, written once the scan completes
+    Json,
+    // one JSON object per file, written as each file is classified
+    NdJson,
+    // aligned columns, written once the scan completes so that the widths are known
+    Table,
+    // a SARIF log, for code-scanning and inventory tools, written once the scan completes
+    Sarif,
+}
+
+// how file paths are reported
+#[derive(PartialEq, Clone, Copy)]
+enum PathStyle {
+    // as listed, i.e. under `./wasm`
+    Listed,
+    Absolute,
+    // relative to the scanned directory
+    Relative,
+    Basename,
+}
+
+struct Options {
+    // only report the N largest modules
+    top: Option<usize>,
+    sort: SortOrder,
+    group_by: GroupBy,
+    scan_code: bool,
+    // read the DWARF debug info, see ParseOptions::deep
+    deep: bool,
+    mode: Mode,
+    format: Format,
+    // summarise with a bar chart of the language counts, rather than the histogram's one line
+    histogram_bars: bool,
+    // summarise with each module contributing its confidence, rather than 1, to its language
+    weighted: bool,
+    // group the unknown modules by their structural fingerprint
+    unknown_clusters: bool,
+    // report the structural fingerprint alongside each module
+    fingerprint: bool,
+    // report the id of every rule that matches each module
+    evidence: bool,
+    // report structural statistics alongside each module
+    stats: bool,
+    // list every import and export alongside each module
+    dump_symbols: bool,
+    // report the counts in GitHub Actions' formats
+    github_summary: bool,
+    // tally the post-MVP proposals used across the modules, instead of their languages
+    feature_summary: bool,
+    // tally the import modules and imports across the modules, instead of their languages, listing this many of the
+    // most frequent
+    import_histogram: Option<usize>,
+    // break the import histogram down by language
+    by_language: bool,
+    // list a representative module of each language
+    examples: Option<Example>,
+    // the number of modules classified in parallel
+    workers: usize,
+    // give up on any module that takes longer than this to parse
+    timeout: Option<Duration>,
+    // how many imports / exports to keep per module, see ParseOptions::max_symbols
+    max_symbols: usize,
+    // only classify a reproducible random sample of this many modules
+    sample: Option<usize>,
+    seed: u64,
+    // look for a base64-encoded module inside files that aren't wasm
+    extract_embedded: bool,
+    // classify each core module inside a component in its own right, rather than the component as a whole
+    flatten_components: bool,
+    // also classify the modules embedded in each module's custom sections
+    recurse_embedded: bool,
+    // only classify the files that are wasm, or gzipped wasm, going by their contents
+    auto_detect: bool,
+    // only print the summary counts, without keeping any per-file results
+    count_only: bool,
+    // only print which languages were found, one per line
+    distinct: bool,
+    // where to record progress, to resume an interrupted scan from
+    checkpoint: Option<PathBuf>,
+    // a list of the files to classify, one per line, instead of those in ./wasm - `-` reads it from stdin
+    paths_from: Option<PathBuf>,
+    // a stored histogram to compare the counts to, and by how much (as a percentage) each is allowed to change
+    baseline: Option<PathBuf>,
+    baseline_tolerance: f64,
+    // rewrite the baseline from this scan, rather than comparing to it
+    update_baseline: bool,
+    paths: PathStyle,
+    // whether to colour the language names, resolved from --color
+    color: bool,
+    // WIT interface ids to look for among the interfaces that components import and export
+    known_interfaces: Vec<String>,
+    // additional detectors from --signatures files
+    signatures: Signatures,
+    // shared libraries with additional detectors
+    #[cfg(feature = "plugins")]
+    plugins: Vec<PathBuf>,
+}
+
+fn parse_args(args: &[String]) -> Result<Options> {
+    let mut color = ColorChoice::Auto;
+    let mut options = Options {
+        top: None,
+        sort: SortOrder::Size,
+        group_by: GroupBy::Language,
+        scan_code: false,
+        deep: false,
+        mode: Mode::Lenient,
+        format: Format::Text,
+        stats: false,
+        dump_symbols: false,
+        github_summary: false,
+        feature_summary: false,
+        import_histogram: None,
+        by_language: false,
+        examples: None,
+        unknown_clusters: false,
+        fingerprint: false,
+        evidence: false,
+        weighted: false,
+        histogram_bars: false,
+        workers: thread::available_parallelism().map_or(1, |n| n.get()),
+        timeout: None,
+        max_symbols: DEFAULT_MAX_SYMBOLS,
+        sample: None,
+        seed: 0,
+        extract_embedded: false,
+        flatten_components: false,
+        recurse_embedded: false,
+        auto_detect: false,
+        count_only: false,
+        distinct: false,
+        checkpoint: None,
+        paths_from: None,
+        baseline: None,
+        baseline_tolerance: 0.0,
+        update_baseline: false,
+        paths: PathStyle::Listed,
+        color: false,
+        known_interfaces: vec![],
+        signatures: Signatures::default(),
+        #[cfg(feature = "plugins")]
+        plugins: vec![],
+    };
+
+    let mut args = args.iter().map(String::as_str);
+    while let Some(arg) = args.next() {
+        match arg {
+            "--top" => {
+                let n = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--top requires a number"))?;
+                options.top = Some(
+                    n.parse()
+                        .map_err(|_| anyhow!("invalid --top value '{n}'"))?,
+                );
             }
-            Payload::ExportSection(s) => {
-                for export in s {
-                    exports.push(export.unwrap());
-                }
+            "--sample" => {
+                let n = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--sample requires a number"))?;
+                options.sample = Some(
+                    n.parse()
+                        .map_err(|_| anyhow!("invalid --sample value '{n}'"))?,
+                );
+            }
+            #[cfg(feature = "plugins")]
+            "--plugin" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--plugin requires a path"))?;
+                options.plugins.push(PathBuf::from(path));
+            }
+            "--seed" => {
+                let n = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--seed requires a number"))?;
+                options.seed = n
+                    .parse()
+                    .map_err(|_| anyhow!("invalid --seed value '{n}'"))?;
+            }
+            "--sort" => {
+                options.sort = match args.next() {
+                    Some("size") => SortOrder::Size,
+                    Some("language") => SortOrder::Language,
+                    _ => bail!("--sort must be one of: size, language"),
+                };
+            }
+            "--examples" => {
+                options.examples = Some(match args.next() {
+                    Some("largest") => Example::Largest,
+                    Some("smallest") => Example::Smallest,
+                    Some("median") => Example::Median,
+                    _ => bail!("--examples must be one of: largest, smallest, median"),
+                });
             }
-            _ => {}
+            "--color" => {
+                color = match args.next() {
+                    Some("auto") => ColorChoice::Auto,
+                    Some("always") => ColorChoice::Always,
+                    Some("never") => ColorChoice::Never,
+                    _ => bail!("--color must be one of: auto, always, never"),
+                };
+            }
+            "--paths" => {
+                options.paths = match args.next() {
+                    Some("absolute") => PathStyle::Absolute,
+                    Some("relative") => PathStyle::Relative,
+                    Some("basename") => PathStyle::Basename,
+                    _ => bail!("--paths must be one of: absolute, relative, basename"),
+                };
+            }
+            "--group-by" => {
+                options.group_by = match args.next() {
+                    Some("language") => GroupBy::Language,
+                    Some("family") => GroupBy::Family,
+                    Some("toolchain") => GroupBy::Toolchain,
+                    _ => bail!("--group-by must be one of: language, family, toolchain"),
+                };
+            }
+            "--mode" => {
+                options.mode = match args.next() {
+                    Some("strict") => Mode::Strict,
+                    Some("lenient") => Mode::Lenient,
+                    _ => bail!("--mode must be one of: strict, lenient"),
+                };
+            }
+            "--scan-code" => options.scan_code = true,
+            "--deep" => options.deep = true,
+            "--stats" => options.stats = true,
+            "--dump-symbols" => options.dump_symbols = true,
+            "--github-summary" => options.github_summary = true,
+            "--feature-summary" => options.feature_summary = true,
+            "--import-histogram" => {
+                let n = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--import-histogram requires a number"))?;
+                options.import_histogram = match n.parse() {
+                    Ok(n) if n > 0 => Some(n),
+                    _ => bail!("invalid --import-histogram value '{n}'"),
+                };
+            }
+            "--by-language" => options.by_language = true,
+            "--unknown-clusters" => options.unknown_clusters = true,
+            "--fingerprint" => options.fingerprint = true,
+            "--evidence" => options.evidence = true,
+            "--weighted" => options.weighted = true,
+            "--histogram-bars" => options.histogram_bars = true,
+            "--extract-embedded" => options.extract_embedded = true,
+            "--flatten-components" => options.flatten_components = true,
+            "--recurse-embedded" => options.recurse_embedded = true,
+            "--auto-detect" => options.auto_detect = true,
+            "--count-only" => options.count_only = true,
+            "--distinct" => options.distinct = true,
+            "--paths-from" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--paths-from requires a file, or - for stdin"))?;
+                options.paths_from = Some(PathBuf::from(path));
+            }
+            "--checkpoint" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--checkpoint requires a path"))?;
+                options.checkpoint = Some(PathBuf::from(path));
+            }
+            "--baseline" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--baseline requires a path"))?;
+                options.baseline = Some(PathBuf::from(path));
+            }
+            "--baseline-tolerance" => {
+                let percent = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--baseline-tolerance requires a percentage"))?;
+                options.baseline_tolerance = match percent.parse() {
+                    Ok(percent) if percent >= 0.0 => percent,
+                    _ => bail!("invalid --baseline-tolerance value '{percent}'"),
+                };
+            }
+            "--update-baseline" => options.update_baseline = true,
+            "--interfaces" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--interfaces requires a path"))?;
+                options.known_interfaces = read_interfaces(Path::new(path))?;
+            }
+            "--signatures" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--signatures requires a path"))?;
+                options.signatures.load(Path::new(path))?;
+            }
+            "--workers" => {
+                let n = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--workers requires a number"))?;
+                options.workers = match n.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => bail!("invalid --workers value '{n}'"),
+                };
+            }
+            "--max-symbols" => {
+                let n = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--max-symbols requires a number"))?;
+                options.max_symbols = match n.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => bail!("invalid --max-symbols value '{n}'"),
+                };
+            }
+            "--timeout" => {
+                let ms = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--timeout requires a number of milliseconds"))?;
+                options.timeout = match ms.parse() {
+                    Ok(ms) if ms > 0 => Some(Duration::from_millis(ms)),
+                    _ => bail!("invalid --timeout value '{ms}'"),
+                };
+            }
+            "--format" => {
+                options.format = match args.next() {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    Some("ndjson") => Format::NdJson,
+                    Some("table") => Format::Table,
+                    Some("sarif") => Format::Sarif,
+                    _ => bail!("--format must be one of: text, json, ndjson, table, sarif"),
+                };
+            }
+            _ => bail!("unrecognised argument '{arg}'"),
+        }
+    }
+
+    // these all need the per-file results that --count-only and --distinct don't keep
+    let without_results = if options.count_only {
+        Some("--count-only")
+    } else if options.distinct {
+        Some("--distinct")
+    } else if options.feature_summary {
+        Some("--feature-summary")
+    } else if options.import_histogram.is_some() {
+        Some("--import-histogram")
+    } else {
+        None
+    };
+    if let Some(flag) = without_results.filter(|_| {
+        options.top.is_some()
+            || options.weighted
+            || options.unknown_clusters
+            || options.fingerprint
+            || options.evidence
+            || options.stats
+            || options.dump_symbols
+            || options.examples.is_some()
+            || options.format != Format::Text
+    }) {
+        bail!("{flag} can't be combined with --top, --weighted, --unknown-clusters, --fingerprint, --evidence, --stats, --dump-symbols, --examples or --format");
+    }
+    if options.update_baseline && options.baseline.is_none() {
+        bail!("--update-baseline requires a --baseline to write to");
+    }
+    // --distinct prints nothing but the languages
+    if options.distinct && options.baseline.is_some() {
+        bail!("--distinct can't be combined with --baseline");
+    }
+    if options.distinct
+        && (options.count_only || options.github_summary || options.group_by != GroupBy::Language)
+    {
+        bail!("--distinct can't be combined with --count-only, --github-summary or --group-by");
+    }
+    // only the language of each file is checkpointed, not its confidence
+    if options.checkpoint.is_some() && options.weighted {
+        bail!("--checkpoint can't be combined with --weighted");
+    }
+    if options.checkpoint.is_some() && options.group_by == GroupBy::Toolchain {
+        bail!("--checkpoint can't be combined with --group-by toolchain");
+    }
+    // the features of each file aren't checkpointed either, and there are no language counts to compare or break down
+    if options.feature_summary
+        && (options.count_only
+            || options.distinct
+            || options.checkpoint.is_some()
+            || options.baseline.is_some()
+            || options.github_summary
+            || options.group_by != GroupBy::Language)
+    {
+        bail!("--feature-summary can't be combined with --count-only, --distinct, --checkpoint, --baseline, --github-summary or --group-by");
+    }
+    if options.import_histogram.is_some()
+        && (options.count_only
+            || options.distinct
+            || options.feature_summary
+            || options.checkpoint.is_some()
+            || options.baseline.is_some()
+            || options.github_summary
+            || options.group_by != GroupBy::Language)
+    {
+        bail!("--import-histogram can't be combined with --count-only, --distinct, --feature-summary, --checkpoint, --baseline, --github-summary or --group-by");
+    }
+    // the bars replace the per-language histogram, so there has to be one
+    if options.histogram_bars
+        && (options.weighted
+            || options.distinct
+            || options.feature_summary
+            || options.import_histogram.is_some()
+            || options.group_by != GroupBy::Language
+            || options.format == Format::Table)
+    {
+        bail!("--histogram-bars can't be combined with --weighted, --distinct, --feature-summary, --import-histogram, --group-by or --format table");
+    }
+    if options.by_language && options.import_histogram.is_none() {
+        bail!("--by-language only applies to --import-histogram");
+    }
+
+    options.color = options.format == Format::Text && color.enabled();
+    Ok(options)
+}
+
+// The shape of the module - how many functions it imports vs. defines, and its function tables, which hint at how
+// heavily it relies on indirect calls
+struct Stats {
+    imported_functions: usize,
+    defined_functions: usize,
+    import_ratio: Option<f64>,
+    tables: usize,
+    // the combined initial size of all tables
+    table_size: u64,
+    element_segments: usize,
+    element_entries: usize,
+    import_modules: Vec<String>,
+}
+
+impl Stats {
+    fn new(module: &WasmModule) -> Stats {
+        Stats {
+            imported_functions: module.imported_functions(),
+            defined_functions: module.defined_functions,
+            import_ratio: module.import_ratio(),
+            tables: module.tables.len(),
+            table_size: module.tables.iter().map(|t| t.initial as u64).sum(),
+            element_segments: module.element_segments,
+            element_entries: module.element_entries,
+            import_modules: module
+                .import_modules
+                .iter()
+                .map(|m| m.to_string())
+                .collect(),
+        }
+    }
+
+    fn fields(&self) -> [(&'static str, Option<f64>); 7] {
+        [
+            ("imported_functions", Some(self.imported_functions as f64)),
+            ("defined_functions", Some(self.defined_functions as f64)),
+            (
+                "import_ratio",
+                self.import_ratio.map(|r| (r * 100.0).round() / 100.0),
+            ),
+            ("tables", Some(self.tables as f64)),
+            ("table_size", Some(self.table_size as f64)),
+            ("element_segments", Some(self.element_segments as f64)),
+            ("element_entries", Some(self.element_entries as f64)),
+        ]
+    }
+}
+
+// The raw import and export names, for mining patterns outside of the tool
+struct Symbols {
+    // `module.name`
+    imports: Vec<String>,
+    exports: Vec<String>,
+}
+
+impl Symbols {
+    fn new(module: &WasmModule) -> Symbols {
+        Symbols {
+            imports: module
+                .imports
+                .iter()
+                .map(|i| format!("{}.{}", i.module, i.name))
+                .collect(),
+            exports: module.exports.iter().map(|e| e.name.to_string()).collect(),
+        }
+    }
+}
+
+// Picks `n` of the paths, by ranking them on a hash of the seed and file name, so that the same seed always selects
+// the same modules from the same directory
+fn sample(paths: Vec<PathBuf>, n: usize, seed: u64) -> Vec<PathBuf> {
+    let rank = |path: &PathBuf| {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let digest = sha256(format!("{seed}:{name}").as_bytes());
+        u64::from_be_bytes(digest[..8].try_into().unwrap())
+    };
+    let mut ranked: Vec<(u64, PathBuf)> = paths.into_iter().map(|p| (rank(&p), p)).collect();
+    ranked.sort();
+    ranked.truncate(n);
+    let mut paths: Vec<PathBuf> = ranked.into_iter().map(|(_, p)| p).collect();
+    paths.sort();
+    paths
+}
+
+// Each module contributes its confidence to its language, and the remainder to an "uncertain" pool, giving fractional
+// counts that don't treat a guess the same as a certainty
+fn weighted_summary(results: &[ScanResult]) -> String {
+    let mut weights: BTreeMap<Language, f64> = BTreeMap::new();
+    let mut uncertain = 0.0;
+    for result in results {
+        let confidence = result.classification.confidence as f64;
+        if confidence > 0.0 {
+            *weights.entry(result.classification.language).or_insert(0.0) += confidence;
+        }
+        uncertain += 1.0 - confidence;
+    }
+    let mut counts: Vec<String> = weights
+        .iter()
+        .map(|(language, weight)| format!("{language:?}: {weight:.1}"))
+        .collect();
+    counts.push(format!("Uncertain: {uncertain:.1}"));
+    format!("{{{}}}", counts.join(", "))
+}
+
+// Groups the unknown modules by fingerprint, largest clusters first - a big cluster is probably a single toolchain that
+// would be worth writing a detector for
+fn unknown_clusters(results: &[ScanResult]) -> String {
+    let mut clusters: BTreeMap<&str, Vec<&ScanResult>> = BTreeMap::new();
+    for result in results {
+        if let Some(fingerprint) = &result.fingerprint {
+            clusters.entry(fingerprint).or_default().push(result);
         }
     }
+    let mut clusters: Vec<_> = clusters.into_iter().collect();
+    clusters.sort_by_key(|(_, members)| Reverse(members.len()));
 
-    WasmModule { imports, exports }
+    let mut lines = vec![format!("unknown clusters ({}):", clusters.len())];
+    for (fingerprint, members) in clusters.iter().take(10) {
+        lines.push(format!("  {} x {fingerprint}", members.len()));
+        lines.push(format!("    e.g. {}", members[0].path.display()));
+    }
+    lines.join("\n")
 }
 
-fn is_emscripten(module: &WasmModule) -> bool {
-    module.any_imports_match(|i| i.name.to_string().contains("emscripten"))
+// A module to look at for each language, e.g. for writing up a survey
+fn examples(results: &[ScanResult], example: Example) -> String {
+    let mut languages: BTreeMap<Language, Vec<&ScanResult>> = BTreeMap::new();
+    for result in results {
+        languages
+            .entry(result.classification.language)
+            .or_default()
+            .push(result);
+    }
+
+    let label = match example {
+        Example::Largest => "largest",
+        Example::Smallest => "smallest",
+        Example::Median => "median-sized",
+    };
+    let mut lines = vec![format!("examples ({label}):")];
+    for (language, mut members) in languages {
+        // ties are broken by path, so the example is the same from run to run
+        members.sort_by(|a, b| a.size.cmp(&b.size).then_with(|| a.path.cmp(&b.path)));
+        let member = match example {
+            Example::Largest => members[members.len() - 1],
+            Example::Smallest => members[0],
+            Example::Median => members[(members.len() - 1) / 2],
+        };
+        lines.push(format!(
+            "  {language:?}: {} ({} bytes)",
+            member.path.display(),
+            member.size
+        ));
+    }
+    lines.join("\n")
 }
 
-fn is_likely_emscripten(module: &WasmModule) -> bool {
-    // Many of the wasm modules have been compressed with this very distinctive pattern. From looking at a number of wasm modules
-    // and inspecting their contents, or the page that hosts them, it seems quite likely this is Emscripten. For example:
-    //
-    // https://tweet2doom.github.io/t2d-explorer.wasm
-    //   => https://github.com/tweet2doom/tweet2doom.github.io - strong evidence of Emscripten
-    //
-    // https://graphonline.ru/script/Graphoffline.Emscripten.wasm - the clue is in the filename!
-    //
-    // https://wsr-starfinder.com/js/stellarium-web-engine.06229ae9.wasm
-    //  => https://github.com/Stellarium/stellarium-web-engine - code makes reference to using Emscripten
-    (module.any_imports_match(|i| i.module == "a" && i.name == "a")
-        && module.any_imports_match(|i| i.module == "a" && i.name == "b"))
+// A `::notice` workflow command, which GitHub Actions shows as an annotation on the run, and a Markdown table for the
+// job summary, if the step has one
+fn github_summary(counts: &BTreeMap<&Language, usize>) -> Result<String> {
+    let notice: Vec<String> = counts
+        .iter()
+        .map(|(language, count)| format!("{}={count}", language.name()))
+        .collect();
 
-    // another distinctive pattern, again, evidence suggests Emscripten
-    // https://tx.me/
-    // => https://github.com/Samsung/rlottie/blob/master/src/wasm/rlottiewasm.cpp - this is a cool project ;-)
-    //
-    // https://demo.harmonicvision.com - Emscripten mentioned in the page source
-    //
-    // https://webcamera.io - uses FFMpeg, which is an Emscripten project
-    || (module.any_imports_match(|i| i.module == "env" && i.name == "a")
-        && module.any_imports_match(|i| i.module == "env" && i.name == "b"))
+    if let Some(path) = env::var_os("GITHUB_STEP_SUMMARY") {
+        let mut table = String::from("| Language | Modules |\n| --- | ---: |\n");
+        for (language, count) in counts {
+            table += &format!("| {} | {count} |\n", language.name());
+        }
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&path)
+            .with_context(|| format!("failed to open {}", path.to_string_lossy()))?;
+        file.write_all(table.as_bytes())?;
+    }
 
-    // exporting malloc is a C giveaway!
-    || module.any_exports_match(|e| e.name == "malloc")
+    Ok(format!("::notice::languages {}", notice.join(" ")))
+}
 
-    // standard memory management functions
-    || module.any_imports_match(|i| i.module == "env" && i.name == "__memory_base")
+// Signatures are tried after the built-in rules, so like plugins they only classify modules that would otherwise be
+// Unknown, and can't override a rule that is known to be accurate
+fn classify_with_signatures(
+    signatures: &Signatures,
+    module: &WasmModule,
+    mut classification: Classification,
+) -> Classification {
+    if classification.language == Language::Unknown {
+        if let Some(signature) = signatures.matching_signature(module) {
+            classification.language = signature.language;
+            classification.confidence = signature.confidence;
+            classification
+                .hints
+                .insert("signature", signature.id.clone());
+        }
+    }
+    classification
 }
 
-fn is_rust(module: &WasmModule) -> bool {
-    module.any_imports_match(|i| {
-        i.name.to_string().contains("wbindgen")
-            || i.name.to_string().contains("wbg")
-            || i.module == "wbg"
-            || i.module == "wbindgen"
-    }) || module.any_exports_match(|e| e.name.to_string().contains("wbindgen"))
+// Plugin detectors extend the end of the rule chain, so only get a say when no built-in rule matched
+#[cfg(feature = "plugins")]
+fn classify_with_plugins(
+    plugins: &Plugins,
+    module: &WasmModule,
+    mut classification: Classification,
+) -> Classification {
+    if classification.language == Language::Unknown {
+        if let Some(detector) = plugins.matching_detector(module) {
+            classification.language = detector.language;
+            // v1 plugins have no way to say how sure they are
+            classification.confidence = 0.5;
+            classification.hints.insert("plugin", detector.id.clone());
+        }
+    }
+    classification
 }
 
-fn is_blazor(module: &WasmModule) -> bool {
-    module.any_imports_match(|i| i.name.to_string().contains("blazor"))
+// The files to classify, sorted, and how many entries had to be skipped. Entries that can't be read - permission
+// errors, broken symlinks, files deleted mid-listing - are reported and skipped rather than failing the whole scan.
+// Subdirectories are skipped too, so symlinked directories can't lead into cycles.
+fn list_files(dir: &Path) -> Result<(Vec<PathBuf>, usize)> {
+    let mut paths = vec![];
+    let mut skipped = 0;
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(e) => {
+                eprintln!("skipping an entry in {}: {e}", dir.display());
+                skipped += 1;
+                continue;
+            }
+        };
+        // follows symlinks, so a broken one is an error here
+        match fs::metadata(&path) {
+            Ok(metadata) if metadata.is_file() => paths.push(path),
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("skipping {}: {e}", path.display());
+                skipped += 1;
+            }
+        }
+    }
+    paths.sort();
+    Ok((paths, skipped))
 }
 
-fn is_go(module: &WasmModule) -> bool {
-    module.any_imports_match(|i| i.module == "go")
+// The files named in a --paths-from list, in the order they are listed, skipping (and counting) those that don't exist.
+// Each line is a path as-is, spaces and all, and blank lines are ignored.
+fn read_path_list(list: &Path) -> Result<(Vec<PathBuf>, usize)> {
+    let contents = if list == Path::new("-") {
+        let mut contents = String::new();
+        std::io::stdin()
+            .read_to_string(&mut contents)
+            .context("failed to read the list of paths from stdin")?;
+        contents
+    } else {
+        fs::read_to_string(list).with_context(|| format!("failed to read {}", list.display()))?
+    };
+    let mut paths = vec![];
+    let mut skipped = 0;
+    for line in contents.lines() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.is_empty() {
+            continue;
+        }
+        let path = PathBuf::from(line);
+        match fs::metadata(&path) {
+            Ok(metadata) if metadata.is_file() => paths.push(path),
+            Ok(_) => {
+                eprintln!("skipping {}: not a file", path.display());
+                skipped += 1;
+            }
+            Err(e) => {
+                eprintln!("skipping {}: {e}", path.display());
+                skipped += 1;
+            }
+        }
+    }
+    Ok((paths, skipped))
 }
 
-fn is_assemblyscript(module: &WasmModule) -> bool {
-    // OK, so this one is *very* hacky! The hyphenate lib (https://github.com/mnater/Hyphenopoly) is found on a number of
-    // websites. It is written in AssemblyScript, and has a variety of different bundles. They all export the function
-    // 'hyphenate'.
-    module.any_exports_match(|e| e.name == "hyphenate")
+// Renders the path of a file found in `root` - or of a module in a .wast script there, which has a `#N` suffix and
+// doesn't exist in its own right - for the output. Only the reported path changes: files are still read, and
+// checkpointed, by the path they were listed with.
+struct PathRenderer {
+    style: PathStyle,
+    root: PathBuf,
+    absolute_root: PathBuf,
 }
 
-fn infer_language(buf: &Vec<u8>) -> Language {
-    let module = parse_wasm(buf);
+impl PathRenderer {
+    fn new(style: PathStyle, root: &Path) -> Result<PathRenderer> {
+        let absolute_root = if style == PathStyle::Absolute {
+            fs::canonicalize(root)
+                .with_context(|| format!("failed to resolve {}", root.display()))?
+        } else {
+            root.to_path_buf()
+        };
+        Ok(PathRenderer {
+            style,
+            root: root.to_path_buf(),
+            absolute_root,
+        })
+    }
 
-    if is_emscripten(&module) {
-        return Language::Emscripten;
+    fn render(&self, path: &Path) -> PathBuf {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        match self.style {
+            PathStyle::Listed => path.to_path_buf(),
+            PathStyle::Absolute => self.absolute_root.join(relative),
+            PathStyle::Relative => relative.to_path_buf(),
+            PathStyle::Basename => path
+                .file_name()
+                .map_or_else(|| path.to_path_buf(), PathBuf::from),
+        }
     }
-    if is_blazor(&module) {
-        return Language::Blazor;
+}
+
+// why a file didn't produce a result
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum Skipped {
+    Unreadable,
+    // without the magic number, or with --auto-detect, not recognised as any of the formats it unwraps
+    NotWasm,
+    // a wasm version this tool can't read
+    UnsupportedVersion,
+    // with --timeout
+    TimedOut,
+}
+
+const WASM_MAGIC: &[u8] = b"\0asm";
+
+// Only the header is read, so that components are the only files read ahead of the scan
+fn is_component_file(path: &Path) -> bool {
+    read_header(path).is_some_and(|header| is_component(&header))
+}
+
+fn is_core_module_file(path: &Path) -> bool {
+    read_header(path).is_some_and(|header| !is_component(&header))
+}
+
+// the magic number and version of a wasm file, or None for files that aren't wasm
+fn read_header(path: &Path) -> Option<[u8; 8]> {
+    let mut header = [0; 8];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .ok()
+        .map(|_| header)
+        .filter(|header| header.starts_with(WASM_MAGIC))
+}
+
+struct ScanResult {
+    path: PathBuf,
+    // SHA-256 of the contents, which identifies the module across crawls
+    hash: String,
+    size: u64,
+    classification: Classification,
+    features: Vec<&'static str>,
+    partial: bool,
+    // with more imports or exports than --max-symbols
+    oversized: bool,
+    // without name and producers sections
+    stripped: bool,
+    stats: Option<Stats>,
+    symbols: Option<Symbols>,
+    // only recorded with --fingerprint, or for unknown modules with --unknown-clusters
+    fingerprint: Option<String>,
+    // the ids of the rules that match, only recorded with --evidence
+    evidence: Option<Vec<&'static str>>,
+}
+
+fn format_result(result: &ScanResult, color: bool) -> String {
+    let mut line = format!(
+        "{}, {}",
+        color::language(result.classification.language, color),
+        result.path.display()
+    );
+    if !result.classification.hints.is_empty() {
+        line += &format!(", {}", format_hints(&result.classification.hints));
+    }
+    if let Some(binding) = result.classification.js_binding {
+        line += &format!(", js_binding={binding}");
+    }
+    if !result.features.is_empty() {
+        line += &format!(", features={}", result.features.join(","));
     }
-    if is_rust(&module) {
-        return Language::Rust;
+    if result.partial {
+        line += ", partial";
     }
-    if is_go(&module) {
-        return Language::Go;
+    if result.oversized {
+        line += ", oversized";
     }
-    if is_assemblyscript(&module) {
-        return Language::AssemblyScript;
+    if let Some(fingerprint) = &result.fingerprint {
+        line += &format!(", fingerprint={fingerprint}");
     }
-    if is_likely_emscripten(&module) {
-        return Language::Emscripten;
+    if let Some(evidence) = &result.evidence {
+        line += &format!(", evidence={}", evidence.join(","));
+    }
+    if let Some(stats) = &result.stats {
+        let fields: Vec<String> = stats
+            .fields()
+            .iter()
+            .filter_map(|(k, v)| v.map(|v| format!("{k}={v}")))
+            .collect();
+        line += &format!(
+            ", {} import_modules={}",
+            fields.join(" "),
+            stats.import_modules.join(",")
+        );
+    }
+    if let Some(symbols) = &result.symbols {
+        for import in &symbols.imports {
+            line += &format!("\n  import {import}");
+        }
+        for export in &symbols.exports {
+            line += &format!("\n  export {export}");
+        }
     }
+    line
+}
 
-    // Unknown modules
-    // 2735d1055ef617dbb1e84cdfa8eb5a9c05f50201a7aa8c06d44533166124fec6.wasm => https://tikzjax.com / webjs / Pascal
+fn result_json(result: &ScanResult) -> Json {
+    let classification = &result.classification;
+    Json::object([
+        ("path", result.path.display().to_string().into()),
+        ("hash", result.hash.as_str().into()),
+        ("language", classification.language.name().into()),
+        (
+            "rule",
+            classification
+                .rule
+                .map_or(Json::Null, |rule| rule.id.into()),
+        ),
+        (
+            "js_binding",
+            classification.js_binding.map_or(Json::Null, Json::from),
+        ),
+        (
+            "runnability",
+            classification.runnability.map_or(Json::Null, Json::from),
+        ),
+        (
+            "hints",
+            Json::object(
+                classification
+                    .hints
+                    .iter()
+                    .map(|(k, v)| (*k, v.as_str().into())),
+            ),
+        ),
+        (
+            "features",
+            Json::Array(result.features.iter().map(|f| (*f).into()).collect()),
+        ),
+        (
+            "min_feature_level",
+            min_feature_level(&result.features).into(),
+        ),
+        (
+            "confidence",
+            Json::Number((classification.confidence as f64 * 100.0).round() / 100.0),
+        ),
+        ("size", result.size.into()),
+        ("partial", result.partial.into()),
+        ("oversized", result.oversized.into()),
+        ("stripped", result.stripped.into()),
+        (
+            "fingerprint",
+            result.fingerprint.as_deref().map_or(Json::Null, Json::from),
+        ),
+        (
+            "evidence",
+            result.evidence.as_ref().map_or(Json::Null, |ids| {
+                Json::Array(ids.iter().map(|id| (*id).into()).collect())
+            }),
+        ),
+        (
+            "stats",
+            match &result.stats {
+                Some(stats) => Json::object(
+                    stats
+                        .fields()
+                        .map(|(k, v)| (k, v.map_or(Json::Null, Json::Number)))
+                        .into_iter()
+                        .chain([(
+                            "import_modules",
+                            Json::Array(
+                                stats
+                                    .import_modules
+                                    .iter()
+                                    .map(|m| m.as_str().into())
+                                    .collect(),
+                            ),
+                        )]),
+                ),
+                None => Json::Null,
+            },
+        ),
+        (
+            "imports",
+            match &result.symbols {
+                Some(symbols) => {
+                    Json::Array(symbols.imports.iter().map(|i| i.as_str().into()).collect())
+                }
+                None => Json::Null,
+            },
+        ),
+        (
+            "exports",
+            match &result.symbols {
+                Some(symbols) => {
+                    Json::Array(symbols.exports.iter().map(|e| e.as_str().into()).collect())
+                }
+                None => Json::Null,
+            },
+        ),
+    ])
+}
 
-    // b8ea049ced002e39f3e32203c3d08f2efa964437887c92c39dd22e50945d7438.wasm => https://github.com/gasman/jsspeccy3 / AssemblyScript
-    return Language::Unknown;
+fn print_result(result: &ScanResult, options: &Options) {
+    match options.format {
+        Format::Text => println!("{}", format_result(result, options.color)),
+        Format::NdJson => println!("{}", result_json(result)),
+        Format::Json | Format::Table | Format::Sarif => {}
+    }
 }
 
-fn main() -> () {
-    let paths = fs::read_dir("./wasm").unwrap();
+// Lays the rows out in columns as wide as their widest cell, with numbers right-aligned, optionally with a border
+fn render_table(header: &[&str], rows: &[Vec<String>], bordered: bool) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: Vec<String>| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| {
+                if cell.parse::<f64>().is_ok() {
+                    format!("{cell:>width$}")
+                } else {
+                    format!("{cell:<width$}")
+                }
+            })
+            .collect();
+        if bordered {
+            format!("| {} |", cells.join(" | "))
+        } else {
+            cells.join("  ").trim_end().to_string()
+        }
+    };
+    let rule = |joint: &str| {
+        let dashes: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+        if bordered {
+            format!("+-{}-+", dashes.join(&format!("-{joint}-")))
+        } else {
+            dashes.join("  ")
+        }
+    };
+
+    let mut lines = vec![];
+    if bordered {
+        lines.push(rule("+"));
+    }
+    lines.push(line(header.iter().map(|h| h.to_string()).collect()));
+    lines.push(rule("+"));
+    lines.extend(rows.iter().map(|row| line(row.clone())));
+    if bordered {
+        lines.push(rule("+"));
+    }
+    lines.join("\n")
+}
 
-    let mut langs = vec![];
+fn results_table(results: &[&ScanResult]) -> String {
+    let rows: Vec<Vec<String>> = results
+        .iter()
+        .map(|r| {
+            vec![
+                r.classification.language.name().to_string(),
+                format!("{:.2}", r.classification.confidence),
+                r.size.to_string(),
+                r.path.display().to_string(),
+            ]
+        })
+        .collect();
+    render_table(&["language", "confidence", "size", "path"], &rows, false)
+}
 
-    for path in paths {
-        let f = path.unwrap();
-        let buf: Vec<u8> = fs::read(f.path()).unwrap();
-        let lang = infer_language(&buf);
-        println!("{:?}, {}", lang, f.path().display());
-        langs.push(lang);
-    }
-
-    let mut counts = HashMap::new();
-    langs.iter().for_each(|val| {
-        counts
-            .entry(val)
-            .and_modify(|count| *count += 1)
-            .or_insert(1);
+fn counts_table<K: std::fmt::Debug>(label: &str, counts: &BTreeMap<K, usize>) -> String {
+    let rows: Vec<Vec<String>> = counts
+        .iter()
+        .map(|(k, count)| vec![format!("{k:?}"), count.to_string()])
+        .collect();
+    render_table(&[label, "modules"], &rows, true)
+}
+
+// One interface id per line, ignoring blank lines and # comments
+fn read_interfaces(path: &Path) -> Result<Vec<String>> {
+    let list =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(list
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+// Ids without a version, e.g. `example:host/logging`, match every version of the interface
+fn is_known_interface(known: &[String], interface: &str) -> bool {
+    let unversioned = interface.split('@').next().unwrap_or(interface);
+    known.iter().any(|id| id == interface || id == unversioned)
+}
+
+// Reads, parses and classifies each input, shared by the full scan and --count-only
+struct Scanner<'a> {
+    options: &'a Options,
+    parse_options: ParseOptions,
+    #[cfg(feature = "plugins")]
+    plugins: Plugins,
+    checkpoint: Option<Mutex<Checkpoint>>,
+}
+
+impl Scanner<'_> {
+    // `buf` is the contents of inputs that have already been read, e.g. modules in .wast scripts. `finish` is given the
+    // file's contents, as read, along with the module and its classification.
+    fn scan<R>(
+        &self,
+        path: &Path,
+        buf: Option<Vec<u8>>,
+        finish: impl FnOnce(&[u8], &WasmModule, Classification) -> R,
+    ) -> Result<R, Skipped> {
+        let options = self.options;
+        let buf: Vec<u8> = match buf.map_or_else(|| fs::read(path), Ok) {
+            Ok(buf) => buf,
+            // e.g. deleted since the directory was listed
+            Err(e) => {
+                eprintln!("skipping {}: {e}", path.display());
+                return Err(Skipped::Unreadable);
+            }
+        };
+        // asset dumps can hold modules saved with the compression they were served with
+        let decompressed = if options.auto_detect && gzip::is_gzip(&buf) {
+            gzip::gunzip(&buf).filter(|module| module.starts_with(WASM_MAGIC))
+        } else {
+            None
+        };
+        let contents = decompressed.as_deref().unwrap_or(&buf);
+        let embedded = if options.extract_embedded && !contents.starts_with(WASM_MAGIC) {
+            extract_embedded(contents)
+        } else {
+            None
+        };
+        if options.auto_detect && embedded.is_none() && !contents.starts_with(WASM_MAGIC) {
+            return Err(Skipped::NotWasm);
+        }
+        let parse_options = ParseOptions {
+            deadline: options.timeout.map(|timeout| Instant::now() + timeout),
+            ..self.parse_options.clone()
+        };
+        let module = parse_wasm_with(embedded.as_deref().unwrap_or(contents), &parse_options);
+        if let Some(e) = module.error {
+            eprintln!("skipping {}: {e}", path.display());
+            return Err(match e {
+                ParseError::NotWasm => Skipped::NotWasm,
+                ParseError::UnsupportedVersion(_) => Skipped::UnsupportedVersion,
+            });
+        }
+        if module.timed_out {
+            eprintln!("skipping {}: timed out", path.display());
+            return Err(Skipped::TimedOut);
+        }
+        let mut classification = classify_with_mode(&module, options.mode);
+        if embedded.is_some() {
+            classification
+                .hints
+                .insert("embedded", "base64".to_string());
+        }
+        if decompressed.is_some() {
+            classification
+                .hints
+                .insert("compression", "gzip".to_string());
+        }
+        if let Some(component) = &module.component {
+            let known: Vec<&str> = component
+                .imports
+                .iter()
+                .chain(&component.exports)
+                .copied()
+                .filter(|interface| is_known_interface(&options.known_interfaces, interface))
+                .collect();
+            if !known.is_empty() {
+                classification
+                    .hints
+                    .insert("known_interfaces", known.join(","));
+            }
+        }
+        let classification = classify_with_signatures(&options.signatures, &module, classification);
+        #[cfg(feature = "plugins")]
+        let classification = classify_with_plugins(&self.plugins, &module, classification);
+        if let Some(checkpoint) = &self.checkpoint {
+            // written once the checkpoint is unlocked, so the other workers can go on recording while it is
+            let snapshot = checkpoint
+                .lock()
+                .unwrap()
+                .record(path, classification.language);
+            // a checkpoint that can't be written doesn't stop the scan, it can be written again at the end
+            if let Err(e) = snapshot.map_or(Ok(()), |snapshot| snapshot.write()) {
+                eprintln!("{e:#}");
+            }
+        }
+        Ok(finish(&buf, &module, classification))
+    }
+
+    // saves the final state of the checkpoint, if there is one
+    fn save_checkpoint(self) -> Result<()> {
+        match self.checkpoint {
+            Some(checkpoint) => checkpoint.into_inner().unwrap().write(),
+            None => Ok(()),
+        }
+    }
+}
+
+// For very large scans where only the histogram is wanted: nothing is kept, or printed, per file - each worker counts
+// the languages it sees and the counts are merged at the end, so memory use doesn't grow with the number of files.
+// With --distinct only the languages that were seen are printed, not their counts.
+fn count_only(
+    scanner: Scanner,
+    inputs: Vec<(PathBuf, Option<Vec<u8>>)>,
+    resumed: BTreeMap<Language, usize>,
+    mut skipped: usize,
+    text_modules: usize,
+) -> Result<()> {
+    let options = scanner.options;
+    // the toolchains are only counted when they are wanted, so as not to split up the counts otherwise
+    let by_toolchain = options.group_by == GroupBy::Toolchain;
+    let outcomes = workers::count(inputs, options.workers, |(path, buf)| {
+        scanner.scan(&path, buf, |_, _, classification| {
+            let toolchain = by_toolchain.then(|| Toolchain::of(&classification).0.to_string());
+            (classification.language, toolchain)
+        })
     });
-    println!();
-    println!("{counts:?}");
-    println!(
+    scanner.save_checkpoint()?;
+    let mut counts = resumed.clone();
+    let mut toolchains = BTreeMap::new();
+    let mut not_wasm = 0;
+    let mut unsupported = 0;
+    let mut timed_out = 0;
+    for (outcome, count) in outcomes {
+        match outcome {
+            Ok((language, toolchain)) => {
+                *counts.entry(language).or_insert(0) += count;
+                if let Some(toolchain) = toolchain {
+                    *toolchains.entry(toolchain).or_insert(0) += count;
+                }
+            }
+            Err(Skipped::Unreadable) => skipped += count,
+            Err(Skipped::NotWasm) => not_wasm += count,
+            Err(Skipped::UnsupportedVersion) => unsupported += count,
+            Err(Skipped::TimedOut) => timed_out += count,
+        }
+    }
+    if options.distinct {
+        let mut languages: Vec<&str> = counts.keys().map(Language::name).collect();
+        languages.sort();
+        for language in languages {
+            println!("{language}");
+        }
+        return Ok(());
+    }
+
+    // --weighted can't be combined with --count-only, as it needs the confidence of each result
+    let mut summary = summary_lines(
+        options,
+        &counts,
+        &resumed,
+        || unreachable!("--weighted with --count-only"),
+        || {
+            toolchains
+                .iter()
+                .map(|(toolchain, count)| (Toolchain(toolchain), *count))
+                .collect()
+        },
+    );
+    summary.extend(skipped_notes(
+        text_modules,
+        skipped,
+        &[not_wasm, unsupported, timed_out],
+    ));
+    if options.github_summary {
+        let counts: BTreeMap<&Language, usize> = counts.iter().map(|(l, c)| (l, *c)).collect();
+        summary.push(github_summary(&counts)?);
+    }
+    println!("{}", summary.join("\n"));
+    check_baseline(options, &counts)
+}
+
+// The head of the summary, shared by the full scan and --count-only: the counts, grouped as --group-by asks, or
+// weighted by confidence with --weighted, then the share left unclassified and the files a checkpoint had already
+// classified. `counts` includes the resumed files. The weights and toolchains are only worked out when they are wanted.
+fn summary_lines<'a, L: Borrow<Language> + Ord + std::fmt::Debug>(
+    options: &Options,
+    counts: &BTreeMap<L, usize>,
+    resumed: &BTreeMap<Language, usize>,
+    weighted: impl FnOnce() -> String,
+    toolchains: impl FnOnce() -> BTreeMap<Toolchain<'a>, usize>,
+) -> Vec<String> {
+    let classified: usize = counts.values().sum();
+    let mut summary = vec![];
+    if options.weighted {
+        summary.push(weighted());
+    } else if options.group_by == GroupBy::Family {
+        let mut families = BTreeMap::new();
+        for (language, count) in counts {
+            *families.entry(language.borrow().family()).or_insert(0) += count;
+        }
+        if options.format == Format::Table {
+            summary.push(counts_table("family", &families));
+        } else {
+            summary.push(format!("{families:?}"));
+        }
+    } else if options.group_by == GroupBy::Toolchain {
+        let toolchains = toolchains();
+        if options.format == Format::Table {
+            summary.push(counts_table("toolchain", &toolchains));
+        } else {
+            summary.push(format!("{toolchains:?}"));
+        }
+    } else if options.format == Format::Table {
+        summary.push(counts_table("language", counts));
+    } else if options.histogram_bars {
+        summary.push(color::bars(counts, options.color, color::terminal_width()));
+    } else {
+        summary.push(color::histogram(counts, options.color));
+    }
+    let unknown = counts
+        .iter()
+        .find(|(language, _)| *(*language).borrow() == Language::Unknown)
+        .map_or(0, |(_, count)| *count);
+    summary.push(format!(
         "{:.0}% unclassified",
-        *counts.get(&Language::Unknown).unwrap() as f32 * 100.0 / langs.len() as f32
+        unknown as f32 * 100.0 / classified as f32
+    ));
+    let resumed: usize = resumed.values().sum();
+    if resumed > 0 {
+        summary.push(format!(
+            "{resumed} files were already classified, according to the checkpoint"
+        ));
+    }
+    summary
+}
+
+// The summary lines for the inputs that didn't produce a result: .wast text modules, unreadable files, and the
+// not-wasm, unsupported version and timed out counts, in that order, as --count-only, --feature-summary and
+// --import-histogram keep them
+fn skipped_notes(
+    text_modules: usize,
+    skipped: usize,
+    [not_wasm, unsupported, timed_out]: &[usize; 3],
+) -> Vec<String> {
+    let mut notes = vec![];
+    if text_modules > 0 {
+        notes.push(format!(
+            "{text_modules} text modules in .wast scripts were skipped, as they need assembling first"
+        ));
+    }
+    if skipped > 0 {
+        notes.push(format!("{skipped} files couldn't be read and were skipped"));
+    }
+    if *not_wasm > 0 {
+        notes.push(format!("{not_wasm} files were skipped as they aren't wasm"));
+    }
+    if *unsupported > 0 {
+        notes.push(format!(
+            "{unsupported} files were skipped as they have a wasm version this tool doesn't support"
+        ));
+    }
+    if *timed_out > 0 {
+        notes.push(format!(
+            "{timed_out} files were skipped as they took longer than the --timeout to parse"
+        ));
+    }
+    notes
+}
+
+// How many of the modules use each post-MVP proposal, as a view of their adoption rather than of the languages. The
+// modules are tallied by their set of features, rather than kept, so this runs in as little memory as --count-only.
+fn feature_summary(
+    scanner: Scanner,
+    inputs: Vec<(PathBuf, Option<Vec<u8>>)>,
+    mut skipped: usize,
+    text_modules: usize,
+) -> Result<()> {
+    let options = scanner.options;
+    let outcomes = workers::count(inputs, options.workers, |(path, buf)| {
+        scanner.scan(&path, buf, |_, module, _| module.features())
+    });
+    let mut modules = 0;
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut skipped_counts = [0; 3];
+    for (outcome, count) in outcomes {
+        match outcome {
+            Ok(features) => {
+                modules += count;
+                for feature in features {
+                    *counts.entry(feature).or_insert(0) += count;
+                }
+            }
+            Err(Skipped::Unreadable) => skipped += count,
+            Err(Skipped::NotWasm) => skipped_counts[0] += count,
+            Err(Skipped::UnsupportedVersion) => skipped_counts[1] += count,
+            Err(Skipped::TimedOut) => skipped_counts[2] += count,
+        }
+    }
+
+    let width = FEATURES.iter().map(|f| f.len()).max().unwrap_or(0);
+    println!("{:width$}  modules  percent", "feature");
+    for feature in FEATURES {
+        if !options.scan_code && CODE_FEATURES.contains(feature) {
+            continue;
+        }
+        let count = *counts.get(feature).unwrap_or(&0);
+        let percent = if modules == 0 {
+            0.0
+        } else {
+            count as f64 * 100.0 / modules as f64
+        };
+        println!("{feature:width$}  {count:>7}  {percent:>6.1}%");
+    }
+    let mut summary = vec![format!("{modules} modules scanned")];
+    if !options.scan_code {
+        summary.push(format!(
+            "{} are only found by walking the code, with --scan-code",
+            CODE_FEATURES.join(", ")
+        ));
+    }
+    summary.extend(skipped_notes(text_modules, skipped, &skipped_counts));
+    println!();
+    println!("{}", summary.join("\n"));
+    Ok(())
+}
+
+// The import modules and imports of a group of modules, with the number of modules importing each
+#[derive(Default)]
+struct ImportTally {
+    modules: usize,
+    import_modules: BTreeMap<String, usize>,
+    imports: BTreeMap<String, usize>,
+}
+
+impl ImportTally {
+    fn add(&mut self, imports: &BTreeSet<(String, String)>, count: usize) {
+        self.modules += count;
+        let import_modules: BTreeSet<&String> = imports.iter().map(|(module, _)| module).collect();
+        for module in import_modules {
+            *self.import_modules.entry(module.clone()).or_insert(0) += count;
+        }
+        for (module, name) in imports {
+            *self.imports.entry(format!("{module}.{name}")).or_insert(0) += count;
+        }
+    }
+
+    fn print(&self, top: usize) {
+        for (heading, counts) in [
+            ("import module", &self.import_modules),
+            ("import", &self.imports),
+        ] {
+            // most frequent first, and alphabetically among equals, so that the output is the same from run to run
+            let mut counts: Vec<(&String, usize)> = counts.iter().map(|(k, v)| (k, *v)).collect();
+            counts.sort_by_key(|(name, count)| (Reverse(*count), *name));
+            counts.truncate(top);
+            let width = counts
+                .iter()
+                .map(|(name, _)| name.len())
+                .chain([heading.len()])
+                .max()
+                .unwrap_or(0);
+            println!("{heading:width$}  modules  percent");
+            for (name, count) in counts {
+                let percent = count as f64 * 100.0 / self.modules as f64;
+                println!("{name:width$}  {count:>7}  {percent:>6.1}%");
+            }
+            println!();
+        }
+    }
+}
+
+// The most frequent import modules and imports, overall or within each language, for finding the signals worth a new
+// rule. Each module counts once towards each of its imports, however many times it imports it. As with
+// --feature-summary, the modules are tallied by their set of imports rather than kept.
+fn import_histogram(
+    scanner: Scanner,
+    inputs: Vec<(PathBuf, Option<Vec<u8>>)>,
+    mut skipped: usize,
+    text_modules: usize,
+    top: usize,
+) -> Result<()> {
+    let options = scanner.options;
+    let outcomes = workers::count(inputs, options.workers, |(path, buf)| {
+        scanner.scan(&path, buf, |_, module, classification| {
+            let imports: BTreeSet<(String, String)> = module
+                .imports
+                .iter()
+                .map(|i| (i.module.to_string(), i.name.to_string()))
+                .collect();
+            (
+                options.by_language.then_some(classification.language),
+                imports,
+            )
+        })
+    });
+    let mut modules = 0;
+    // keyed by language with --by-language, otherwise all under None
+    let mut tallies: BTreeMap<Option<Language>, ImportTally> = BTreeMap::new();
+    let mut skipped_counts = [0; 3];
+    for (outcome, count) in outcomes {
+        match outcome {
+            Ok((language, imports)) => {
+                modules += count;
+                tallies.entry(language).or_default().add(&imports, count);
+            }
+            Err(Skipped::Unreadable) => skipped += count,
+            Err(Skipped::NotWasm) => skipped_counts[0] += count,
+            Err(Skipped::UnsupportedVersion) => skipped_counts[1] += count,
+            Err(Skipped::TimedOut) => skipped_counts[2] += count,
+        }
+    }
+
+    for (language, tally) in &tallies {
+        if let Some(language) = language {
+            let plural = if tally.modules == 1 { "" } else { "s" };
+            println!("{language} ({} module{plural})", tally.modules);
+            println!();
+        }
+        tally.print(top);
+    }
+    let mut summary = vec![format!("{modules} modules scanned")];
+    summary.extend(skipped_notes(text_modules, skipped, &skipped_counts));
+    println!("{}", summary.join("\n"));
+    Ok(())
+}
+
+fn check_baseline<L: Borrow<Language>>(
+    options: &Options,
+    counts: &BTreeMap<L, usize>,
+) -> Result<()> {
+    match &options.baseline {
+        Some(path) => baseline::check(
+            path,
+            counts,
+            options.baseline_tolerance,
+            options.update_baseline,
+        ),
+        None => Ok(()),
+    }
+}
+
+fn main() -> Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("analyze") {
+        return analyze::analyze(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("explain") {
+        return explain::explain(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("evaluate") {
+        return evaluate::evaluate(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("--json-schema") {
+        println!("{}", schema::schema());
+        return Ok(());
+    }
+    if args.first().map(String::as_str) == Some("--diff") {
+        return diff::diff(&args[1..]);
+    }
+    // --merge is the older spelling, kept working alongside the subcommand
+    if matches!(args.first().map(String::as_str), Some("merge" | "--merge")) {
+        return merge::merge(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("--calibrate") {
+        return calibrate::calibrate(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("--watch") {
+        return watch::watch(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("serve") {
+        #[cfg(feature = "server")]
+        return serve::serve(&args[1..]);
+        #[cfg(not(feature = "server"))]
+        bail!("serve needs the server feature, e.g. cargo run --features server -- serve");
+    }
+
+    let options = parse_args(&args)?;
+    let parse_options = ParseOptions {
+        scan_code: options.scan_code,
+        max_symbols: options.max_symbols,
+        deep: options.deep,
+        ..ParseOptions::default()
+    };
+    // listed paths are relative to the working directory, rather than to ./wasm
+    let root = Path::new(if options.paths_from.is_some() {
+        "."
+    } else {
+        "./wasm"
+    });
+    let (mut paths, mut skipped) = match &options.paths_from {
+        Some(list) => read_path_list(list)?,
+        None => list_files(root)?,
+    };
+    let total = paths.len();
+    if let Some(n) = options.sample {
+        paths = sample(paths, n, options.seed);
+    }
+
+    #[cfg(feature = "plugins")]
+    let plugins = {
+        let mut plugins = Plugins::default();
+        for path in &options.plugins {
+            plugins.load(path)?;
+        }
+        plugins
+    };
+
+    // each module in a .wast script is classified in its own right, identified by its ordinal within the script, as are
+    // the core modules in a component with --flatten-components, and with --recurse-embedded those in a module's custom
+    // sections, alongside the module itself
+    let mut inputs: Vec<(PathBuf, Option<Vec<u8>>)> = vec![];
+    let mut text_modules = 0;
+    for path in paths {
+        if path.extension().is_some_and(|e| e == "wast") {
+            let script = match fs::read_to_string(&path) {
+                Ok(script) => wast::read_script(&script),
+                Err(e) => {
+                    eprintln!("skipping {}: {e}", path.display());
+                    skipped += 1;
+                    continue;
+                }
+            };
+            text_modules += script.text_modules;
+            for (index, buf) in script.binary_modules {
+                inputs.push((
+                    PathBuf::from(format!("{}#{index}", path.display())),
+                    Some(buf),
+                ));
+            }
+        } else if options.flatten_components && is_component_file(&path) {
+            let buf = match fs::read(&path) {
+                Ok(buf) => buf,
+                Err(e) => {
+                    eprintln!("skipping {}: {e}", path.display());
+                    skipped += 1;
+                    continue;
+                }
+            };
+            let modules = core_modules(&buf);
+            if modules.is_empty() {
+                inputs.push((path, Some(buf)));
+                continue;
+            }
+            for (index, module) in modules.into_iter().enumerate() {
+                inputs.push((
+                    PathBuf::from(format!("{}#module[{index}]", path.display())),
+                    Some(module.to_vec()),
+                ));
+            }
+        } else if options.recurse_embedded && is_core_module_file(&path) {
+            let buf = match fs::read(&path) {
+                Ok(buf) => buf,
+                Err(e) => {
+                    eprintln!("skipping {}: {e}", path.display());
+                    skipped += 1;
+                    continue;
+                }
+            };
+            let embedded: Vec<(PathBuf, Option<Vec<u8>>)> = custom_section_modules(&buf)
+                .into_iter()
+                .map(|(sections, module)| {
+                    (
+                        PathBuf::from(format!("{}{sections}", path.display())),
+                        Some(module.to_vec()),
+                    )
+                })
+                .collect();
+            // the module itself is read again when it is classified, rather than held onto until then
+            inputs.push((path, None));
+            inputs.extend(embedded);
+        } else {
+            inputs.push((path, None));
+        }
+    }
+
+    // files that a previous, interrupted, run already classified only contribute their recorded language to the counts
+    let checkpoint = options
+        .checkpoint
+        .as_deref()
+        .map(Checkpoint::load)
+        .transpose()?;
+    let mut resumed = BTreeMap::new();
+    if let Some(checkpoint) = &checkpoint {
+        inputs.retain(|(path, _)| match checkpoint.language(path) {
+            Some(language) => {
+                *resumed.entry(language).or_insert(0) += 1;
+                false
+            }
+            None => true,
+        });
+    }
+
+    let renderer = PathRenderer::new(options.paths, root)?;
+    let scanner = Scanner {
+        options: &options,
+        parse_options,
+        #[cfg(feature = "plugins")]
+        plugins,
+        checkpoint: checkpoint.map(Mutex::new),
+    };
+    if options.feature_summary {
+        return feature_summary(scanner, inputs, skipped, text_modules);
+    }
+    if let Some(top) = options.import_histogram {
+        return import_histogram(scanner, inputs, skipped, text_modules, top);
+    }
+    if options.count_only || options.distinct {
+        return count_only(scanner, inputs, resumed, skipped, text_modules);
+    }
+
+    let results = workers::map_ordered(
+        inputs,
+        options.workers,
+        |(path, buf)| {
+            scanner.scan(&path, buf, |buf, module, classification| {
+                let fingerprint = (options.fingerprint
+                    || options.unknown_clusters && classification.language == Language::Unknown)
+                    .then(|| module.fingerprint());
+                ScanResult {
+                    path: renderer.render(&path),
+                    hash: sha256_hex(buf),
+                    size: buf.len() as u64,
+                    classification,
+                    features: module.features(),
+                    partial: module.partial,
+                    oversized: module.oversized,
+                    stripped: module.is_stripped(),
+                    stats: options.stats.then(|| Stats::new(module)),
+                    symbols: options.dump_symbols.then(|| Symbols::new(module)),
+                    fingerprint,
+                    evidence: options
+                        .evidence
+                        .then(|| matching_rules(module).iter().map(|rule| rule.id).collect()),
+                }
+            })
+        },
+        |result| {
+            if let (Ok(result), None) = (result, options.top) {
+                print_result(result, &options);
+            }
+        },
     );
+    scanner.save_checkpoint()?;
+    skipped += results
+        .iter()
+        .filter(|r| r.as_ref().err() == Some(&Skipped::Unreadable))
+        .count();
+    let not_wasm = results
+        .iter()
+        .filter(|r| r.as_ref().err() == Some(&Skipped::NotWasm))
+        .count();
+    let unsupported = results
+        .iter()
+        .filter(|r| r.as_ref().err() == Some(&Skipped::UnsupportedVersion))
+        .count();
+    let timed_out = results
+        .iter()
+        .filter(|r| r.as_ref().err() == Some(&Skipped::TimedOut))
+        .count();
+    let results: Vec<ScanResult> = results.into_iter().flatten().collect();
+
+    let mut listed: Vec<&ScanResult> = results.iter().collect();
+    if let Some(top) = options.top {
+        listed.sort_by_key(|r| Reverse(r.size));
+        listed.truncate(top);
+        if options.sort == SortOrder::Language {
+            listed.sort_by_key(|r| format!("{:?}", r.classification.language));
+        }
+        for result in &listed {
+            match options.format {
+                Format::Text => println!(
+                    "{}, {} bytes",
+                    format_result(result, options.color),
+                    result.size
+                ),
+                _ => print_result(result, &options),
+            }
+        }
+    }
+    if options.format == Format::Json {
+        println!(
+            "{}",
+            Json::Array(listed.iter().map(|r| result_json(r)).collect())
+        );
+    }
+    if options.format == Format::Sarif {
+        println!("{}", sarif::report(&listed));
+    }
+    if options.format == Format::Table {
+        println!("{}", results_table(&listed));
+    }
+
+    let mut counts = tally(results.iter().map(|r| &r.classification.language));
+    for (language, count) in &resumed {
+        *counts.entry(language).or_insert(0) += count;
+    }
+    let mut summary = summary_lines(
+        &options,
+        &counts,
+        &resumed,
+        || weighted_summary(&results),
+        || tally(results.iter().map(|r| Toolchain::of(&r.classification))),
+    );
+    let auxiliary = results
+        .iter()
+        .filter(|r| {
+            r.classification
+                .hints
+                .get("bindgen_role")
+                .map(String::as_str)
+                == Some("auxiliary")
+        })
+        .count();
+    if auxiliary > 0 {
+        summary.push(format!(
+            "{auxiliary} Rust modules are auxiliary wasm-bindgen modules, which may belong to another module's app"
+        ));
+    }
+    summary.extend(skipped_notes(
+        text_modules,
+        skipped,
+        &[not_wasm, unsupported, timed_out],
+    ));
+    let partial = results.iter().filter(|r| r.partial).count();
+    if partial > 0 {
+        summary.push(format!("{partial} modules were only partially parsed"));
+    }
+    let oversized = results.iter().filter(|r| r.oversized).count();
+    if oversized > 0 {
+        summary.push(format!(
+            "{oversized} modules had more than --max-symbols imports or exports, and were classified on the first of them"
+        ));
+    }
+    if options.sample.is_some() && results.len() < total {
+        summary.push(format!(
+            "sample of {} of {total} modules (seed {}), extrapolated to the full set:",
+            results.len(),
+            options.seed
+        ));
+        let scale = total as f64 / results.len() as f64;
+        let extrapolated: Vec<String> = counts
+            .iter()
+            .map(|(language, count)| format!("{language:?}: ~{:.0}", *count as f64 * scale))
+            .collect();
+        summary.push(format!("{{{}}}", extrapolated.join(", ")));
+    }
+
+    if options.unknown_clusters {
+        summary.push(unknown_clusters(&results));
+    }
+    if let Some(example) = options.examples {
+        summary.push(examples(&results, example));
+    }
+    if options.github_summary {
+        summary.push(github_summary(&counts)?);
+    }
+
+    // keep stdout parseable when it is carrying JSON
+    if matches!(options.format, Format::Text | Format::Table) {
+        println!();
+        println!("{}", summary.join("\n"));
+    } else {
+        eprintln!("{}", summary.join("\n"));
+    }
+
+    check_baseline(&options, &counts)
 }
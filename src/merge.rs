@@ -0,0 +1,55 @@
+use crate::color;
+use crate::diff::{field, read_results};
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use wasm_lang_inference::json::Json;
+use wasm_lang_inference::{tally, Language};
+
+// Combines the JSON results from scans of the shards of a corpus, e.g. made on different machines, into one. Each
+// module is kept once, by content hash, however many shards it turned up in, and written as a single --format json
+// array that --diff can compare like any other scan. The summary goes to stderr, as with --format json.
+pub fn merge(args: &[String]) -> Result<()> {
+    if args.is_empty() {
+        bail!("usage: wasm-lang-inference merge <scan.json>...");
+    }
+
+    let mut files = 0;
+    // in the order first seen, with the index of each hash in it
+    let mut modules: Vec<(Language, Json)> = vec![];
+    let mut index: BTreeMap<String, usize> = BTreeMap::new();
+    let mut disagreements = 0;
+    for path in args {
+        for (hash, result) in read_results(path)? {
+            files += 1;
+            let name = field(path, &result, "language")?;
+            let language: Language = name
+                .parse()
+                .with_context(|| format!("{path}: result for module {hash}"))?;
+            match index.get(&hash) {
+                Some(&i) => {
+                    if modules[i].0 != language {
+                        disagreements += 1;
+                    }
+                }
+                None => {
+                    index.insert(hash, modules.len());
+                    modules.push((language, result));
+                }
+            }
+        }
+    }
+
+    let counts = tally(modules.iter().map(|(language, _)| *language));
+    let results: Vec<Json> = modules.into_iter().map(|(_, result)| result).collect();
+    println!("{}", Json::Array(results));
+    eprintln!("{}", color::histogram(&counts, false));
+    eprintln!(
+        "{files} results across {} scans, of {} unique modules",
+        args.len(),
+        index.len()
+    );
+    if disagreements > 0 {
+        eprintln!("{disagreements} results disagreed with the first scan of the same module, which was kept");
+    }
+    Ok(())
+}
@@ -0,0 +1,925 @@
+use crate::component::{is_component, read_component, ComponentInterfaces};
+use crate::dwarf::{read_compile_unit, CompileUnit, DwarfSections};
+use anyhow::Result;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::time::Instant;
+use wasmparser::{
+    BinaryReader, DataKind, ElementKind, Export, FuncType, FunctionBody, Import, InitExpr,
+    MemoryType, Name, NameSectionReader, NamingReader, Operator, Parser, Payload,
+    ProducersSectionReader, SectionReader, TableType, Type, TypeDef, TypeRef,
+};
+
+// An entry from the producers section, e.g. field "language", name "Rust", version "" or field "processed-by",
+// name "rustc", version "1.61.0"
+pub struct Producer<'a> {
+    pub field: &'a str,
+    pub name: &'a str,
+    pub version: &'a str,
+}
+
+// The debug names from the name section, which is usually stripped from production builds. Toolchains write whichever
+// subsections they have names for, so any of these can be missing.
+#[derive(Default)]
+pub struct NameInfo<'a> {
+    // the name given to the module as a whole, e.g. the crate or output file name
+    pub module: Option<&'a str>,
+    pub functions: Vec<&'a str>,
+    // parameter and local variable names, across all functions
+    pub locals: Vec<&'a str>,
+}
+
+// Why a module couldn't be parsed at all, as opposed to being partially parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    // doesn't start with the `\0asm` magic number, so isn't wasm at all
+    NotWasm,
+    // a version field other than the core module's 1 - and not a component's - e.g. from a pre-release toolchain
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::NotWasm => write!(f, "not a wasm module"),
+            ParseError::UnsupportedVersion(version) => {
+                write!(f, "unsupported wasm version {version:#x}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// What a dynamically linked module (an Emscripten side module, or any `wasm-ld -shared` output) asks of the dynamic
+// linker, from its `dylink.0` section, or the `dylink` section of older toolchains
+#[derive(Default, Debug, PartialEq)]
+pub struct DylinkInfo<'a> {
+    // the memory and table space to reserve for it, in bytes and entries, and their alignments as powers of 2
+    pub memory_size: u32,
+    pub memory_alignment: u32,
+    pub table_size: u32,
+    pub table_alignment: u32,
+    // the other side modules it needs loading first, by file name, e.g. `libfoo.so`
+    pub needed: Vec<&'a str>,
+}
+
+pub struct WasmModule<'a> {
+    // the function types from the type section, indexed by function imports / definitions
+    pub types: Vec<FuncType>,
+    pub imports: Vec<Import<'a>>,
+    // the distinct modules imported from (`env`, `wbg`, `wasi_snapshot_preview1`, ...), a compact fingerprint of the
+    // toolchain in itself
+    pub import_modules: BTreeSet<&'a str>,
+    pub exports: Vec<Export<'a>>,
+    pub data: Vec<&'a [u8]>,
+    pub custom_sections: Vec<&'a str>,
+    pub producers: Vec<Producer<'a>>,
+    pub names: NameInfo<'a>,
+    pub size: usize,
+    // the number of functions defined in the module, i.e. excluding imports
+    pub defined_functions: usize,
+    // exception tags defined in the tag section, from the exception handling proposal - imported tags are in `imports`
+    pub defined_tags: usize,
+    // imported and defined tables, in index order. Large function tables suggest C++ vtables or Go's runtime.
+    pub tables: Vec<TableType>,
+    // whether a memory / table is imported rather than declared by the module itself, as it is when the host, or
+    // another module, owns it
+    pub imports_memory: bool,
+    pub imports_table: bool,
+    // whether any memory, imported or defined, is shared between threads (the threads proposal) or indexed by i64
+    // (memory64)
+    pub uses_shared_memory: bool,
+    pub uses_memory64: bool,
+    pub element_segments: usize,
+    // the total number of entries across all element segments
+    pub element_entries: usize,
+    // from the sourceMappingURL custom section, typically only present in development builds
+    pub source_map_url: Option<String>,
+    // from the build_id section, a content hash for symbolication that newer LLVM-based toolchains emit
+    pub build_id: Option<Vec<u8>>,
+    // the combined size of the DWARF `.debug_*` custom sections
+    pub debug_info_size: usize,
+    // the first compilation unit of the DWARF debug info, only read with ParseOptions::deep
+    pub dwarf: Option<CompileUnit<'a>>,
+    // from the dylink.0 (or dylink) section, only present in dynamically linked modules
+    pub dylink: Option<DylinkInfo<'a>>,
+    // whether the type section declares struct / array types from the GC proposal
+    pub uses_gc: bool,
+    // `i31ref`, which GC languages use for unboxed small integers (e.g. Scheme's fixnums), and `externref`, for holding
+    // JS values directly - both best-effort for GC modules, see read_gc_types
+    pub uses_i31: bool,
+    pub uses_externref: bool,
+    // whether any table holds externrefs rather than funcrefs, which, like having more than one table, needs
+    // reference types - see table_count
+    pub has_externref_table: bool,
+    // whether a global initializer, or data / element segment offset, does arithmetic, see is_extended_const
+    pub uses_extended_const: bool,
+    // the following are only populated when the code section is scanned, see ParseOptions
+    pub uses_tail_calls: bool,
+    pub uses_simd: bool,
+    // relaxed SIMD is a separate, newer proposal - modules using it are counted for both
+    pub uses_relaxed_simd: bool,
+    // the sign-extension and non-trapping float-to-int proposals, which clang enables by default (since LLVM 16 and 20
+    // respectively), so modules without either tend to be from older or minimal toolchains
+    pub uses_sign_ext: bool,
+    pub uses_sat_float_conv: bool,
+    // whether the export section, and so every section ahead of it, was read in full - also set once a later section
+    // is reached in a module without exports
+    pub exports_read: bool,
+    // set when a section failed to parse, in which case imports / exports hold whatever was read before the error
+    pub partial: bool,
+    // for components, rather than core modules, in which case none of the fields above are read
+    pub component: Option<ComponentInterfaces<'a>>,
+    // set when the module couldn't be read at all, in which case none of the fields above are read either
+    pub error: Option<ParseError>,
+    // set when parsing was abandoned at ParseOptions::deadline, in which case the fields above are incomplete
+    pub timed_out: bool,
+    // set when there were more imports or exports than ParseOptions::max_symbols, in which case only that many were
+    // kept, and the module is classified on those
+    pub oversized: bool,
+}
+
+impl WasmModule<'_> {
+    pub(crate) fn any_imports_match<F: Fn(&Import) -> bool>(&self, f: F) -> bool {
+        self.imports.iter().any(f)
+    }
+
+    pub(crate) fn any_exports_match<F: Fn(&Export) -> bool>(&self, f: F) -> bool {
+        self.exports.iter().any(f)
+    }
+
+    pub(crate) fn data_contains(&self, needle: &[u8]) -> bool {
+        self.data
+            .iter()
+            .any(|d| d.windows(needle.len()).any(|w| w == needle))
+    }
+
+    // imported and defined tables together - MVP modules have at most one, the indirect function table
+    pub fn table_count(&self) -> usize {
+        self.tables.len()
+    }
+
+    // the post-MVP proposals used by the module, as far as the code scan found, in the order of FEATURES
+    pub fn features(&self) -> Vec<&'static str> {
+        let mut features = vec![];
+        if self.table_count() > 1 || self.has_externref_table {
+            features.push("reference-types");
+        }
+        if self.uses_gc {
+            features.push("gc");
+        }
+        if self.uses_shared_memory {
+            features.push("threads");
+        }
+        if self.uses_memory64 {
+            features.push("memory64");
+        }
+        if self.uses_exception_tags() {
+            features.push("exception-handling");
+        }
+        if self.uses_tail_calls {
+            features.push("tail-calls");
+        }
+        if self.uses_extended_const {
+            features.push("extended-const");
+        }
+        if self.uses_sign_ext {
+            features.push("sign-ext");
+        }
+        if self.uses_sat_float_conv {
+            features.push("nontrapping-fptoint");
+        }
+        if self.uses_simd {
+            features.push("simd");
+        }
+        if self.uses_relaxed_simd {
+            features.push("relaxed-simd");
+        }
+        features
+    }
+
+    // the signature of an imported function, if it is one and its type was read
+    pub fn import_signature(&self, import: &Import) -> Option<&FuncType> {
+        match import.ty {
+            TypeRef::Func(index) => self.types.get(index as usize),
+            _ => None,
+        }
+    }
+
+    pub fn imported_functions(&self) -> usize {
+        self.imports
+            .iter()
+            .filter(|i| matches!(i.ty, TypeRef::Func(_)))
+            .count()
+    }
+
+    // Imported functions per defined function. Modules that lean on JS glue (wasm-bindgen, Emscripten) import a lot
+    // relative to what they define, whereas standalone WASI and hand-written modules import very little. None for
+    // modules that don't define any functions.
+    pub fn import_ratio(&self) -> Option<f64> {
+        (self.defined_functions > 0)
+            .then(|| self.imported_functions() as f64 / self.defined_functions as f64)
+    }
+
+    // A coarse structural signature - the modules imported from, the styles of export name, the features used and the
+    // order of magnitude of the size - which is shared by modules from the same toolchain even when their detail
+    // differs, e.g. `imports[env,wasi_snapshot_preview1] exports[minified,underscored] features[simd] size[<1M]`. Each
+    // part is listed in a fixed order, so it is the same for the same module on every run and platform. Used to
+    // cluster unknown modules into candidates for new detectors, and reported with --fingerprint for grouping modules
+    // elsewhere. Features are only seen when the code section is scanned, see ParseOptions.
+    pub fn fingerprint(&self) -> String {
+        let modules: Vec<&str> = self.import_modules.iter().copied().collect();
+        let mut shapes: Vec<&str> = self
+            .exports
+            .iter()
+            .map(|e| {
+                if e.name.len() <= 2 {
+                    "minified"
+                } else if e.name.starts_with('_') {
+                    "underscored"
+                } else if e
+                    .name
+                    .contains(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                {
+                    "mangled"
+                } else {
+                    "plain"
+                }
+            })
+            .collect();
+        shapes.sort();
+        shapes.dedup();
+        format!(
+            "imports[{}] exports[{}] features[{}] size[{}]",
+            modules.join(","),
+            shapes.join(","),
+            self.features().join(","),
+            size_bucket(self.size)
+        )
+    }
+
+    // Relocatable object files (`.o`), which still need to be linked - the linker leaves a `linking` section (and
+    // `reloc.*` sections) behind in them, and strips it from the final module
+    pub fn is_object_file(&self) -> bool {
+        self.has_custom_section("linking")
+    }
+
+    // Crash dumps in the wasm coredump format (wasmtime's, and the `wasm-coredump-*` tools'), which are wasm modules
+    // holding the crashed instance's memory and globals, with the process, stack and instance details in `core*`
+    // custom sections - always a `core` one. They are a snapshot of a program, rather than a program.
+    pub fn is_coredump(&self) -> bool {
+        self.has_custom_section("core")
+    }
+
+    // Companion files that only carry the DWARF split out of a module (Emscripten's -gseparate-dwarf, or the `.dwp`
+    // style of other toolchains), which are mostly `.debug_*` sections with no code of their own. They aren't
+    // programs, so they are kept apart rather than being classified as the language they describe.
+    pub fn is_debug_info(&self) -> bool {
+        self.defined_functions == 0 && self.debug_info_size * 2 > self.size
+    }
+
+    // Production builds are usually stripped of their name and producers sections, leaving the import / export names as
+    // the only symbols to go on
+    pub fn is_stripped(&self) -> bool {
+        !self.has_custom_section("name") && !self.has_custom_section("producers")
+    }
+
+    // too short to hold the magic number and version, so nothing was parsed
+    pub fn is_empty(&self) -> bool {
+        self.size < HEADER_LEN
+    }
+
+    pub(crate) fn has_custom_section(&self, name: &str) -> bool {
+        self.custom_sections.contains(&name)
+    }
+
+    // whether the module throws or catches wasm exceptions, which need a tag to identify them
+    pub(crate) fn uses_exception_tags(&self) -> bool {
+        self.defined_tags > 0 || self.any_imports_match(|i| matches!(i.ty, TypeRef::Tag(_)))
+    }
+
+    pub(crate) fn imports_shared_memory(&self) -> bool {
+        self.any_imports_match(|i| matches!(i.ty, TypeRef::Memory(m) if m.shared))
+    }
+}
+
+// the smallest power of ten that the size is under, from 1K up to 100M
+fn size_bucket(size: usize) -> &'static str {
+    const BUCKETS: [(usize, &str); 6] = [
+        (1_000, "<1K"),
+        (10_000, "<10K"),
+        (100_000, "<100K"),
+        (1_000_000, "<1M"),
+        (10_000_000, "<10M"),
+        (100_000_000, "<100M"),
+    ];
+    BUCKETS
+        .iter()
+        .find(|(limit, _)| size < *limit)
+        .map_or(">=100M", |(_, bucket)| bucket)
+}
+
+const HEADER_LEN: usize = 8;
+pub(crate) const WASM_MAGIC: &[u8] = b"\0asm";
+const CORE_VERSION: u32 = 1;
+
+#[derive(Clone)]
+pub struct ParseOptions {
+    // scanning every instruction in the code section is far slower than reading the other sections, so it is opt-in
+    pub scan_code: bool,
+    // when to give up on the module, for untrusted inputs that might take pathologically long to scan
+    pub deadline: Option<Instant>,
+    // how many imports, and separately exports, to collect before ignoring the rest, as many of the rules go through
+    // every one of them - see WasmModule::oversized
+    pub max_symbols: usize,
+    // the DWARF debug info can run to many times the size of the code, so reading it is opt-in too
+    pub deep: bool,
+}
+
+// Far more than any real module has: the largest Emscripten apps import a few thousand functions
+pub const DEFAULT_MAX_SYMBOLS: usize = 100_000;
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            scan_code: false,
+            deadline: None,
+            max_symbols: DEFAULT_MAX_SYMBOLS,
+            deep: false,
+        }
+    }
+}
+
+// Every proposal that WasmModule::features reports, in the same order
+pub const FEATURES: &[&str] = &[
+    "reference-types",
+    "gc",
+    "threads",
+    "memory64",
+    "exception-handling",
+    "tail-calls",
+    "extended-const",
+    "sign-ext",
+    "nontrapping-fptoint",
+    "simd",
+    "relaxed-simd",
+];
+
+// the features that are only found by the code scan, see ParseOptions::scan_code
+pub const CODE_FEATURES: &[&str] = &[
+    "tail-calls",
+    "sign-ext",
+    "nontrapping-fptoint",
+    "simd",
+    "relaxed-simd",
+];
+
+// the features standardised in WebAssembly 2.0 - all the others but threads followed in 3.0
+const WASM_2_FEATURES: &[&str] = &["reference-types", "sign-ext", "nontrapping-fptoint", "simd"];
+
+// The earliest release of the spec that covers all of `features` (as given by WasmModule::features): "1.0" for the MVP,
+// "2.0" or "3.0", suffixed with "+threads" for shared memories, which no release includes yet. It is only a lower bound
+// without the code scan, which finds the features that only appear in function bodies.
+pub fn min_feature_level(features: &[&str]) -> &'static str {
+    let threads = features.contains(&"threads");
+    let others = features.iter().filter(|feature| **feature != "threads");
+    let level = others.fold(1, |level, feature| {
+        level.max(if WASM_2_FEATURES.contains(feature) {
+            2
+        } else {
+            3
+        })
+    });
+    match (level, threads) {
+        (1, false) => "1.0",
+        (1, true) => "1.0+threads",
+        (2, false) => "2.0",
+        (2, true) => "2.0+threads",
+        (_, false) => "3.0",
+        (_, true) => "3.0+threads",
+    }
+}
+
+// For inspecting the structure of a module in its own right, rather than classifying it: the same as parse_wasm,
+// except that a module that couldn't be read at all is an error, rather than an empty WasmModule with `error` set.
+// Modules that were partially parsed are still Ok, with `partial` set.
+pub fn parse(buf: &[u8]) -> std::result::Result<WasmModule<'_>, ParseError> {
+    let module = parse_wasm(buf);
+    match module.error {
+        Some(e) => Err(e),
+        None => Ok(module),
+    }
+}
+
+pub fn parse_wasm(buf: &[u8]) -> WasmModule<'_> {
+    parse_wasm_with(buf, &ParseOptions::default())
+}
+
+pub fn parse_wasm_with<'a>(buf: &'a [u8], options: &ParseOptions) -> WasmModule<'a> {
+    parse_wasm_reusing(buf, options, vec![], vec![])
+}
+
+// The same as parse_wasm_with, but filling the given (empty) type and table lists rather than allocating new ones.
+// They are the only lists that don't borrow from the buffer, so the only ones that can outlive it, see Classifier.
+pub(crate) fn parse_wasm_reusing<'a>(
+    buf: &'a [u8],
+    options: &ParseOptions,
+    types: Vec<FuncType>,
+    tables: Vec<TableType>,
+) -> WasmModule<'a> {
+    let mut module = WasmModule {
+        types,
+        imports: vec![],
+        import_modules: BTreeSet::new(),
+        exports: vec![],
+        data: vec![],
+        custom_sections: vec![],
+        producers: vec![],
+        names: NameInfo::default(),
+        size: buf.len(),
+        defined_functions: 0,
+        defined_tags: 0,
+        tables,
+        imports_memory: false,
+        imports_table: false,
+        uses_shared_memory: false,
+        uses_memory64: false,
+        element_segments: 0,
+        element_entries: 0,
+        source_map_url: None,
+        build_id: None,
+        debug_info_size: 0,
+        dwarf: None,
+        dylink: None,
+        uses_gc: false,
+        uses_i31: false,
+        uses_externref: false,
+        has_externref_table: false,
+        uses_extended_const: false,
+        uses_tail_calls: false,
+        uses_simd: false,
+        uses_relaxed_simd: false,
+        uses_sign_ext: false,
+        uses_sat_float_conv: false,
+        exports_read: false,
+        partial: false,
+        component: None,
+        error: None,
+        timed_out: false,
+        oversized: false,
+    };
+    if module.is_empty() {
+        return module;
+    }
+    if !buf.starts_with(WASM_MAGIC) {
+        module.error = Some(ParseError::NotWasm);
+        return module;
+    }
+    if is_component(buf) {
+        let mut interfaces = ComponentInterfaces::default();
+        module.partial = read_component(buf, &mut interfaces).is_err();
+        module.component = Some(interfaces);
+        return module;
+    }
+    let version = u32::from_le_bytes(buf[4..HEADER_LEN].try_into().unwrap());
+    if version != CORE_VERSION {
+        module.error = Some(ParseError::UnsupportedVersion(version));
+        return module;
+    }
+
+    // Some modules are truncated, or have corrupt sections, after the import / export sections. Rather than discarding
+    // everything, keep what was collected up to the point of failure - it is usually enough to classify the module.
+    if read_sections(buf, options, &mut module).is_err() {
+        module.partial = true;
+    }
+
+    module
+}
+
+// leading bytes of the rec, sub, sub final, struct and array type definitions
+const GC_TYPE_FORMS: [u8; 5] = [0x4e, 0x50, 0x4f, 0x5f, 0x5e];
+
+// The remainder of a type section, from the first GC type that wasmparser couldn't read. Without a parser for them,
+// look for the i31ref (0x6c) and externref (0x6f) value types among its bytes. Type indices are signed LEBs, so can't
+// be either byte, but counts can, so a module with 108 or 111 fields in a struct would be misreported - rare enough to
+// live with.
+fn read_gc_types(types: &[u8], module: &mut WasmModule) {
+    if types.contains(&0x6c) {
+        module.uses_i31 = true;
+    }
+    if types.contains(&0x6f) {
+        module.uses_externref = true;
+    }
+}
+
+fn read_memory(memory: &MemoryType, module: &mut WasmModule) {
+    module.uses_shared_memory |= memory.shared;
+    module.uses_memory64 |= memory.memory64;
+}
+
+fn read_sections<'a>(
+    buf: &'a [u8],
+    options: &ParseOptions,
+    module: &mut WasmModule<'a>,
+) -> Result<()> {
+    let mut dwarf = DwarfSections::default();
+    for payload in Parser::new(0).parse_all(buf) {
+        if is_past(options.deadline) {
+            module.timed_out = true;
+            return Ok(());
+        }
+        let payload = payload?;
+        if !matches!(
+            payload,
+            Payload::Version { .. }
+                | Payload::TypeSection(_)
+                | Payload::ImportSection(_)
+                | Payload::FunctionSection(_)
+                | Payload::TableSection(_)
+                | Payload::MemorySection(_)
+                | Payload::TagSection(_)
+                | Payload::GlobalSection(_)
+                | Payload::ExportSection(_)
+                | Payload::CustomSection { .. }
+                // a prefix that stops at a section boundary ends here too, so the end says nothing about the exports
+                | Payload::End(_)
+        ) {
+            module.exports_read = true;
+        }
+        match payload {
+            Payload::TypeSection(mut s) => {
+                for _ in 0..s.get_count() {
+                    let form = buf.get(s.original_position()).copied();
+                    match s.read() {
+                        Ok(TypeDef::Func(ty)) => {
+                            if ty
+                                .params
+                                .iter()
+                                .chain(ty.returns.iter())
+                                .any(|t| *t == Type::ExternRef)
+                            {
+                                module.uses_externref = true;
+                            }
+                            module.types.push(ty);
+                        }
+                        // wasmparser doesn't understand GC types, but the type section is self-contained so we can
+                        // note their presence and move on to the remaining sections
+                        Err(_) if form.is_some_and(|f| GC_TYPE_FORMS.contains(&f)) => {
+                            module.uses_gc = true;
+                            read_gc_types(&buf[s.original_position()..s.range().end], module);
+                            break;
+                        }
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+            }
+            Payload::ImportSection(s) => {
+                for import in s {
+                    if module.imports.len() == options.max_symbols {
+                        module.oversized = true;
+                        break;
+                    }
+                    let import = import?;
+                    match import.ty {
+                        TypeRef::Table(table) => {
+                            if table.element_type == Type::ExternRef {
+                                module.uses_externref = true;
+                                module.has_externref_table = true;
+                            }
+                            module.tables.push(table);
+                            module.imports_table = true;
+                        }
+                        TypeRef::Memory(memory) => {
+                            module.imports_memory = true;
+                            read_memory(&memory, module);
+                        }
+                        TypeRef::Global(global) if global.content_type == Type::ExternRef => {
+                            module.uses_externref = true;
+                        }
+                        _ => {}
+                    }
+                    module.import_modules.insert(import.module);
+                    module.imports.push(import);
+                }
+            }
+            Payload::FunctionSection(s) => {
+                module.defined_functions = s.get_count() as usize;
+            }
+            Payload::MemorySection(s) => {
+                for memory in s {
+                    read_memory(&memory?, module);
+                }
+            }
+            Payload::TagSection(s) => {
+                module.defined_tags = s.get_count() as usize;
+            }
+            Payload::TableSection(s) => {
+                for table in s {
+                    let table = table?;
+                    if table.element_type == Type::ExternRef {
+                        module.uses_externref = true;
+                        module.has_externref_table = true;
+                    }
+                    module.tables.push(table);
+                }
+            }
+            Payload::ExportSection(s) => {
+                for export in s {
+                    if module.exports.len() == options.max_symbols {
+                        module.oversized = true;
+                        break;
+                    }
+                    module.exports.push(export?);
+                }
+                module.exports_read = true;
+            }
+            Payload::GlobalSection(s) => {
+                for global in s {
+                    if is_extended_const(&global?.init_expr) {
+                        module.uses_extended_const = true;
+                    }
+                }
+            }
+            Payload::ElementSection(s) => {
+                for element in s {
+                    let element = element?;
+                    if let ElementKind::Active { init_expr, .. } = &element.kind {
+                        if is_extended_const(init_expr) {
+                            module.uses_extended_const = true;
+                        }
+                    }
+                    module.element_segments += 1;
+                    module.element_entries +=
+                        element.items.get_items_reader()?.get_count() as usize;
+                }
+            }
+            Payload::DataSection(s) => {
+                for data in s {
+                    let data = data?;
+                    if let DataKind::Active { init_expr, .. } = &data.kind {
+                        if is_extended_const(init_expr) {
+                            module.uses_extended_const = true;
+                        }
+                    }
+                    module.data.push(data.data);
+                }
+            }
+            Payload::CustomSection {
+                name,
+                data,
+                data_offset,
+                ..
+            } => {
+                module.custom_sections.push(name);
+                if name.starts_with(".debug_") {
+                    module.debug_info_size += data.len();
+                    if options.deep {
+                        dwarf.add(name, data);
+                    }
+                }
+                if name == "producers" {
+                    read_producers(data, data_offset, module);
+                }
+                if name == "dylink.0" || name == "dylink" {
+                    let mut dylink = DylinkInfo::default();
+                    let mut reader = BinaryReader::new(data);
+                    // as with the producers section, keep whatever could be read
+                    let _ = if name == "dylink.0" {
+                        read_dylink(&mut reader, &mut dylink)
+                    } else {
+                        read_legacy_dylink(&mut reader, &mut dylink)
+                    };
+                    module.dylink = Some(dylink);
+                }
+                if name == "name" {
+                    read_names(data, data_offset, module);
+                }
+                if name == "sourceMappingURL" {
+                    module.source_map_url = BinaryReader::new(data)
+                        .read_string()
+                        .ok()
+                        .map(str::to_string);
+                }
+                if name == "build_id" {
+                    let mut reader = BinaryReader::new(data);
+                    module.build_id = reader
+                        .read_var_u32()
+                        .and_then(|len| reader.read_bytes(len as usize))
+                        .ok()
+                        .map(<[u8]>::to_vec);
+                }
+            }
+            Payload::CodeSectionEntry(body) if options.scan_code => {
+                scan_function(buf, &body, options.deadline, module)?;
+            }
+            _ => {}
+        }
+    }
+
+    if options.deep {
+        module.dwarf = read_compile_unit(&dwarf);
+    }
+    Ok(())
+}
+
+// A malformed producers section isn't worth failing the parse for, so keep whatever could be read
+fn read_producers<'a>(data: &'a [u8], offset: usize, module: &mut WasmModule<'a>) {
+    let fields = match ProducersSectionReader::new(data, offset) {
+        Ok(fields) => fields,
+        Err(_) => return,
+    };
+    for field in fields.into_iter().flatten() {
+        let values = match field.get_producer_field_values_reader() {
+            Ok(values) => values,
+            Err(_) => continue,
+        };
+        for value in values.into_iter().flatten() {
+            module.producers.push(Producer {
+                field: field.name,
+                name: value.name,
+                version: value.version,
+            });
+        }
+    }
+}
+
+// dylink.0 subsections - the export and import info subsections only flag TLS and weak symbols, so are skipped
+const DYLINK_MEM_INFO: u8 = 1;
+const DYLINK_NEEDED: u8 = 2;
+
+fn read_dylink<'a>(reader: &mut BinaryReader<'a>, dylink: &mut DylinkInfo<'a>) -> Result<()> {
+    while !reader.eof() {
+        let kind = reader.read_u8()?;
+        let len = reader.read_var_u32()? as usize;
+        let mut subsection = BinaryReader::new(reader.read_bytes(len)?);
+        match kind {
+            DYLINK_MEM_INFO => read_dylink_mem_info(&mut subsection, dylink)?,
+            DYLINK_NEEDED => read_dylink_needed(&mut subsection, dylink)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+// The original encoding, which has the same fields as dylink.0's memory info and needed subsections, one after the
+// other, without the subsection headers
+fn read_legacy_dylink<'a>(
+    reader: &mut BinaryReader<'a>,
+    dylink: &mut DylinkInfo<'a>,
+) -> Result<()> {
+    read_dylink_mem_info(reader, dylink)?;
+    read_dylink_needed(reader, dylink)
+}
+
+fn read_dylink_mem_info(reader: &mut BinaryReader, dylink: &mut DylinkInfo) -> Result<()> {
+    dylink.memory_size = reader.read_var_u32()?;
+    dylink.memory_alignment = reader.read_var_u32()?;
+    dylink.table_size = reader.read_var_u32()?;
+    dylink.table_alignment = reader.read_var_u32()?;
+    Ok(())
+}
+
+fn read_dylink_needed<'a>(
+    reader: &mut BinaryReader<'a>,
+    dylink: &mut DylinkInfo<'a>,
+) -> Result<()> {
+    for _ in 0..reader.read_var_u32()? {
+        dylink.needed.push(reader.read_string()?);
+    }
+    Ok(())
+}
+
+// all SIMD instructions, relaxed or not, share the 0xfd prefix byte
+const SIMD_PREFIX: u8 = 0xfd;
+
+// As with the producers section, a malformed name section shouldn't fail the parse
+fn read_names<'a>(data: &'a [u8], offset: usize, module: &mut WasmModule<'a>) {
+    let names = match NameSectionReader::new(data, offset) {
+        Ok(names) => names,
+        Err(_) => return,
+    };
+    for name in names.into_iter().flatten() {
+        match name {
+            Name::Module(name) => module.names.module = name.get_name().ok(),
+            Name::Function(map) => read_naming(map.get_map(), &mut module.names.functions),
+            Name::Local(indirect) => {
+                if let Ok(mut functions) = indirect.get_indirect_map() {
+                    for _ in 0..functions.get_indirect_count() {
+                        match functions.read() {
+                            Ok(function) => {
+                                read_naming(function.get_map(), &mut module.names.locals)
+                            }
+                            Err(_) => break,
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn read_naming<'a>(reader: wasmparser::Result<NamingReader<'a>>, names: &mut Vec<&'a str>) {
+    if let Ok(mut reader) = reader {
+        for _ in 0..reader.get_count() {
+            match reader.read() {
+                Ok(naming) => names.push(naming.name),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+// The extended-const proposal allows `i32.add` / `i32.sub` / `i32.mul` (0x6a - 0x6c) and their i64 counterparts
+// (0x7c - 0x7e) in constant expressions, where the MVP only has a single `*.const` or `global.get`. LLVM emits them
+// with -mextended-const, e.g. `global.get $__memory_base; i32.const 1024; i32.add` for the data offsets of PIC modules,
+// which would otherwise need relocating at startup. An expression that can't be read is taken not to use them.
+fn is_extended_const(expr: &InitExpr) -> bool {
+    expr.get_operators_reader().into_iter().any(|op| {
+        matches!(
+            op,
+            Ok(Operator::I32Add
+                | Operator::I32Sub
+                | Operator::I32Mul
+                | Operator::I64Add
+                | Operator::I64Sub
+                | Operator::I64Mul)
+        )
+    })
+}
+
+fn is_past(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+// how many instructions to scan between looking at the clock, which is slow by comparison
+const DEADLINE_INTERVAL: usize = 4096;
+
+fn scan_function(
+    buf: &[u8],
+    body: &FunctionBody,
+    deadline: Option<Instant>,
+    module: &mut WasmModule,
+) -> Result<()> {
+    for (i, op) in body
+        .get_operators_reader()?
+        .into_iter_with_offsets()
+        .enumerate()
+    {
+        if i % DEADLINE_INTERVAL == DEADLINE_INTERVAL - 1 && is_past(deadline) {
+            module.timed_out = true;
+            return Ok(());
+        }
+        let (op, offset) = op?;
+        if buf.get(offset) == Some(&SIMD_PREFIX) {
+            module.uses_simd = true;
+        }
+        match op {
+            Operator::ReturnCall { .. } | Operator::ReturnCallIndirect { .. } => {
+                module.uses_tail_calls = true;
+            }
+            // 0xc0 - 0xc4
+            Operator::I32Extend8S
+            | Operator::I32Extend16S
+            | Operator::I64Extend8S
+            | Operator::I64Extend16S
+            | Operator::I64Extend32S => {
+                module.uses_sign_ext = true;
+            }
+            // 0xfc 0x00 - 0xfc 0x07
+            Operator::I32TruncSatF32S
+            | Operator::I32TruncSatF32U
+            | Operator::I32TruncSatF64S
+            | Operator::I32TruncSatF64U
+            | Operator::I64TruncSatF32S
+            | Operator::I64TruncSatF32U
+            | Operator::I64TruncSatF64S
+            | Operator::I64TruncSatF64U => {
+                module.uses_sat_float_conv = true;
+            }
+            Operator::I8x16RelaxedSwizzle
+            | Operator::I32x4RelaxedTruncSatF32x4S
+            | Operator::I32x4RelaxedTruncSatF32x4U
+            | Operator::I32x4RelaxedTruncSatF64x2SZero
+            | Operator::I32x4RelaxedTruncSatF64x2UZero
+            | Operator::F32x4Fma
+            | Operator::F32x4Fms
+            | Operator::F64x2Fma
+            | Operator::F64x2Fms
+            | Operator::I8x16LaneSelect
+            | Operator::I16x8LaneSelect
+            | Operator::I32x4LaneSelect
+            | Operator::I64x2LaneSelect
+            | Operator::F32x4RelaxedMin
+            | Operator::F32x4RelaxedMax
+            | Operator::F64x2RelaxedMin
+            | Operator::F64x2RelaxedMax => {
+                module.uses_relaxed_simd = true;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,182 @@
+// Loads additional detectors from shared libraries at runtime, for heuristics that can't be upstreamed. The C ABI is
+// described in plugins/wasm_lang_inference.h, with an example in plugins/sample.c.
+use crate::{Language, WasmModule};
+use anyhow::{anyhow, bail, Result};
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+pub const ABI_VERSION: u32 = 1;
+
+#[repr(C)]
+struct WliStr {
+    ptr: *const u8,
+    len: usize,
+}
+
+impl WliStr {
+    fn new(s: &str) -> WliStr {
+        WliStr {
+            ptr: s.as_ptr(),
+            len: s.len(),
+        }
+    }
+}
+
+#[repr(C)]
+pub struct WliModule {
+    import_modules: *const WliStr,
+    import_names: *const WliStr,
+    import_count: usize,
+    export_names: *const WliStr,
+    export_count: usize,
+    custom_sections: *const WliStr,
+    custom_section_count: usize,
+    size: usize,
+}
+
+type MatchFn = extern "C" fn(module: *const WliModule) -> bool;
+
+#[repr(C)]
+struct WliDetector {
+    id: *const c_char,
+    language: *const c_char,
+    description: *const c_char,
+    // a NULL function pointer is None, rather than undefined behaviour
+    matches: Option<MatchFn>,
+}
+
+type EntryPoint = unsafe extern "C" fn(abi_version: u32, count: *mut usize) -> *const WliDetector;
+
+const ENTRY_POINT: &CStr = c"wasm_lang_inference_detectors";
+const RTLD_NOW: c_int = 2;
+
+extern "C" {
+    fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    fn dlerror() -> *mut c_char;
+}
+
+fn dl_error() -> String {
+    // SAFETY: dlerror returns NULL or a NUL-terminated message
+    unsafe {
+        let message = dlerror();
+        if message.is_null() {
+            "unknown error".to_string()
+        } else {
+            CStr::from_ptr(message).to_string_lossy().into_owned()
+        }
+    }
+}
+
+unsafe fn c_string(s: *const c_char) -> String {
+    CStr::from_ptr(s).to_string_lossy().into_owned()
+}
+
+pub struct PluginDetector {
+    pub id: String,
+    pub language: Language,
+    pub description: String,
+    matches: MatchFn,
+}
+
+// The detectors from every loaded plugin, in load order. Libraries are never unloaded, so their functions remain valid
+// for the life of the process.
+#[derive(Default)]
+pub struct Plugins {
+    detectors: Vec<PluginDetector>,
+}
+
+impl Plugins {
+    pub fn load(&mut self, path: &Path) -> Result<()> {
+        let filename = CString::new(path.as_os_str().as_bytes())?;
+        // SAFETY: loading a library runs its initialisers, which is the trust the user places in a plugin
+        let library = unsafe { dlopen(filename.as_ptr(), RTLD_NOW) };
+        if library.is_null() {
+            bail!("failed to load plugin {}: {}", path.display(), dl_error());
+        }
+        // SAFETY: the symbol is documented to have the EntryPoint signature
+        let entry_point: EntryPoint = unsafe {
+            let symbol = dlsym(library, ENTRY_POINT.as_ptr());
+            if symbol.is_null() {
+                bail!("{} is not a plugin: {}", path.display(), dl_error());
+            }
+            std::mem::transmute::<*mut c_void, EntryPoint>(symbol)
+        };
+
+        let mut count = 0;
+        // SAFETY: the plugin returns NULL, or an array of `count` detectors with static lifetime
+        let detectors = unsafe { entry_point(ABI_VERSION, &mut count) };
+        if detectors.is_null() {
+            bail!(
+                "{} doesn't support plugin ABI version {ABI_VERSION}",
+                path.display()
+            );
+        }
+        for i in 0..count {
+            // SAFETY: as above, with NUL-terminated strings
+            let (detector, id, language, description) = unsafe {
+                let detector = &*detectors.add(i);
+                (
+                    detector,
+                    c_string(detector.id),
+                    c_string(detector.language),
+                    c_string(detector.description),
+                )
+            };
+            let Some(matches) = detector.matches else {
+                bail!(
+                    "{}: detector '{id}' has no matches function",
+                    path.display()
+                );
+            };
+            self.detectors.push(PluginDetector {
+                id,
+                language: language
+                    .parse()
+                    .map_err(|_| anyhow!("{}: unknown language '{language}'", path.display()))?,
+                description,
+                matches,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn detectors(&self) -> &[PluginDetector] {
+        &self.detectors
+    }
+
+    // the first plugin detector that matches the module
+    pub fn matching_detector(&self, module: &WasmModule) -> Option<&PluginDetector> {
+        if self.detectors.is_empty() {
+            return None;
+        }
+        let import_modules: Vec<WliStr> = module
+            .imports
+            .iter()
+            .map(|i| WliStr::new(i.module))
+            .collect();
+        let import_names: Vec<WliStr> =
+            module.imports.iter().map(|i| WliStr::new(i.name)).collect();
+        let export_names: Vec<WliStr> =
+            module.exports.iter().map(|e| WliStr::new(e.name)).collect();
+        let custom_sections: Vec<WliStr> = module
+            .custom_sections
+            .iter()
+            .map(|s| WliStr::new(s))
+            .collect();
+        let view = WliModule {
+            import_modules: import_modules.as_ptr(),
+            import_names: import_names.as_ptr(),
+            import_count: import_names.len(),
+            export_names: export_names.as_ptr(),
+            export_count: export_names.len(),
+            custom_sections: custom_sections.as_ptr(),
+            custom_section_count: custom_sections.len(),
+            size: module.size,
+        };
+        self.detectors
+            .iter()
+            .find(|detector| (detector.matches)(&view))
+    }
+}
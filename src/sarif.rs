@@ -0,0 +1,111 @@
+use crate::ScanResult;
+use std::collections::{BTreeMap, BTreeSet};
+use wasm_lang_inference::json::Json;
+use wasm_lang_inference::{format_hints, Language};
+
+// Static Analysis Results Interchange Format, see https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html.
+// Only the parts needed to carry an inventory: a rule per language, and a result per module pointing at the file, so
+// that code-scanning dashboards can list and filter the modules by language.
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+fn rule_id(language: Language) -> String {
+    format!("language/{}", language.name())
+}
+
+// SARIF locations are URIs, relative to the directory that was scanned
+fn artifact_uri(result: &ScanResult) -> String {
+    let path = result.path.display().to_string().replace('\\', "/");
+    path.strip_prefix("./").unwrap_or(&path).replace(' ', "%20")
+}
+
+pub fn report(results: &[&ScanResult]) -> Json {
+    let languages: Vec<Language> = results
+        .iter()
+        .map(|r| r.classification.language)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let rule_index: BTreeMap<Language, usize> = languages
+        .iter()
+        .enumerate()
+        .map(|(index, language)| (*language, index))
+        .collect();
+
+    let rules = languages.iter().map(|language| {
+        Json::object([
+            ("id", rule_id(*language).into()),
+            ("name", format!("{language:?}").into()),
+            (
+                "shortDescription",
+                Json::object([(
+                    "text",
+                    format!("WebAssembly module classified as {language:?}").into(),
+                )]),
+            ),
+        ])
+    });
+
+    let results = results.iter().map(|result| {
+        let classification = &result.classification;
+        let mut message = format!("{:?}", classification.language);
+        if let Some(rule) = classification.rule {
+            message += &format!(
+                " ({}, confidence {})",
+                rule.description, classification.confidence
+            );
+        }
+        if !classification.hints.is_empty() {
+            message += &format!(", {}", format_hints(&classification.hints));
+        }
+        Json::object([
+            ("ruleId", rule_id(classification.language).into()),
+            ("ruleIndex", rule_index[&classification.language].into()),
+            // an inventory rather than a problem to fix
+            ("kind", "informational".into()),
+            ("level", "none".into()),
+            ("message", Json::object([("text", message.into())])),
+            (
+                "locations",
+                Json::Array(vec![Json::object([(
+                    "physicalLocation",
+                    Json::object([(
+                        "artifactLocation",
+                        Json::object([("uri", artifact_uri(result).into())]),
+                    )]),
+                )])]),
+            ),
+            (
+                "properties",
+                Json::object([
+                    ("sha256", result.hash.as_str().into()),
+                    (
+                        "evidence",
+                        classification.rule.map_or(Json::Null, |r| r.id.into()),
+                    ),
+                ]),
+            ),
+        ])
+    });
+
+    Json::object([
+        ("$schema", SCHEMA.into()),
+        ("version", "2.1.0".into()),
+        (
+            "runs",
+            Json::Array(vec![Json::object([
+                (
+                    "tool",
+                    Json::object([(
+                        "driver",
+                        Json::object([
+                            ("name", env!("CARGO_PKG_NAME").into()),
+                            ("version", env!("CARGO_PKG_VERSION").into()),
+                            ("rules", Json::Array(rules.collect())),
+                        ]),
+                    )]),
+                ),
+                ("results", Json::Array(results.collect())),
+            ])]),
+        ),
+    ])
+}
@@ -0,0 +1,217 @@
+use wasm_lang_inference::json::Json;
+use wasm_lang_inference::Language;
+
+// JSON Schema (draft 2020-12) for the output of --format json and ndjson, for consumers to validate against and
+// generate types from. Written out by hand, like the JSON itself - tests/schema.rs checks that the two agree.
+const SCHEMA: &str = "https://json-schema.org/draft/2020-12/schema";
+
+fn of_type(ty: &str) -> Json {
+    Json::object([("type", ty.into())])
+}
+
+fn typed(ty: &str, description: &str) -> Json {
+    Json::object([("type", ty.into()), ("description", description.into())])
+}
+
+fn nullable(ty: Json, description: &str) -> Json {
+    Json::object([
+        ("type", Json::Array(vec![ty, "null".into()])),
+        ("description", description.into()),
+    ])
+}
+
+fn strings() -> Json {
+    Json::object([("type", "array".into()), ("items", of_type("string"))])
+}
+
+fn nullable_strings(description: &str) -> Json {
+    Json::object([
+        ("type", Json::Array(vec!["array".into(), "null".into()])),
+        ("items", of_type("string")),
+        ("description", description.into()),
+    ])
+}
+
+fn stats() -> Json {
+    Json::object([
+        ("type", Json::Array(vec!["object".into(), "null".into()])),
+        (
+            "description",
+            "structural statistics, only with --stats".into(),
+        ),
+        (
+            "properties",
+            Json::object([
+                ("imported_functions", of_type("integer")),
+                ("defined_functions", of_type("integer")),
+                (
+                    "import_ratio",
+                    nullable(
+                        "number".into(),
+                        "imported functions per defined function, null without defined functions",
+                    ),
+                ),
+                ("tables", of_type("integer")),
+                (
+                    "table_size",
+                    typed("integer", "the combined initial size of all tables"),
+                ),
+                ("element_segments", of_type("integer")),
+                ("element_entries", of_type("integer")),
+                ("import_modules", strings()),
+            ]),
+        ),
+        ("additionalProperties", false.into()),
+    ])
+}
+
+fn result() -> Json {
+    let languages = Language::ALL
+        .iter()
+        .map(|language| language.name().into())
+        .collect();
+    let properties = [
+        (
+            "path",
+            typed(
+                "string",
+                "the file, or `script.wast#N` for the Nth module in a .wast script",
+            ),
+        ),
+        (
+            "hash",
+            typed("string", "SHA-256 of the file contents, hex-encoded"),
+        ),
+        (
+            "language",
+            Json::object([
+                ("enum", Json::Array(languages)),
+                (
+                    "description",
+                    "a stable name, never changed or reused once released".into(),
+                ),
+            ]),
+        ),
+        (
+            "rule",
+            nullable(
+                "string".into(),
+                "the id of the rule that determined the language, a stable name like the language's",
+            ),
+        ),
+        (
+            "js_binding",
+            nullable(
+                "string".into(),
+                "the JS interop layer the module expects, e.g. wasm-bindgen",
+            ),
+        ),
+        (
+            "runnability",
+            nullable(
+                "string".into(),
+                "what it takes to run the module: standalone, wasi or needs-js-glue",
+            ),
+        ),
+        (
+            "hints",
+            Json::object([
+                ("type", "object".into()),
+                ("additionalProperties", of_type("string")),
+                (
+                    "description",
+                    "additional detail that doesn't change the language".into(),
+                ),
+            ]),
+        ),
+        ("features", strings()),
+        (
+            "min_feature_level",
+            typed(
+                "string",
+                "the earliest spec release covering the features, e.g. 2.0, or 3.0+threads",
+            ),
+        ),
+        (
+            "confidence",
+            Json::object([
+                ("type", "number".into()),
+                ("minimum", 0usize.into()),
+                ("maximum", 1usize.into()),
+            ]),
+        ),
+        ("size", typed("integer", "in bytes")),
+        (
+            "partial",
+            typed(
+                "boolean",
+                "whether parsing stopped early at a malformed section",
+            ),
+        ),
+        (
+            "oversized",
+            typed(
+                "boolean",
+                "whether there were too many imports or exports to keep them all, see --max-symbols",
+            ),
+        ),
+        (
+            "stripped",
+            typed(
+                "boolean",
+                "whether the module has neither a name nor a producers section",
+            ),
+        ),
+        (
+            "fingerprint",
+            nullable(
+                "string".into(),
+                "the structural fingerprint, only with --fingerprint or, for unknown modules, --unknown-clusters",
+            ),
+        ),
+        (
+            "evidence",
+            nullable_strings("the id of every rule that matches, in the order they are tried, only with --evidence"),
+        ),
+        ("stats", stats()),
+        (
+            "imports",
+            nullable_strings("`module.name` of each import, only with --dump-symbols"),
+        ),
+        (
+            "exports",
+            nullable_strings("the name of each export, only with --dump-symbols"),
+        ),
+    ];
+    let required = properties.iter().map(|(name, _)| (*name).into()).collect();
+    Json::object([
+        ("type", "object".into()),
+        ("properties", Json::object(properties)),
+        ("required", Json::Array(required)),
+        ("additionalProperties", false.into()),
+    ])
+}
+
+// --format ndjson writes one result per line, and --format json an array of them. The summary always goes to stderr
+// as text, so isn't part of the schema.
+pub fn schema() -> Json {
+    Json::object([
+        ("$schema", SCHEMA.into()),
+        ("title", "wasm-lang-inference results".into()),
+        (
+            "description",
+            "The results of --format json, or each line of --format ndjson".into(),
+        ),
+        (
+            "oneOf",
+            Json::Array(vec![
+                Json::object([("$ref", "#/$defs/result".into())]),
+                Json::object([
+                    ("type", "array".into()),
+                    ("items", Json::object([("$ref", "#/$defs/result".into())])),
+                ]),
+            ]),
+        ),
+        ("$defs", Json::object([("result", result())])),
+    ])
+}
@@ -0,0 +1,252 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+use wasm_lang_inference::hash::sha256_hex;
+use wasm_lang_inference::json::Json;
+use wasm_lang_inference::{classify, min_feature_level, parse};
+
+const DEFAULT_MAX_BODY: usize = 64 * 1024 * 1024;
+// the request line and headers, which are tiny for the requests this serves
+const MAX_HEAD: usize = 16 * 1024;
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+// Classifies modules POSTed to /classify, for services that would otherwise shell out to the CLI, with a /health
+// endpoint for load balancers. Just enough HTTP/1.1 for that, by hand: one request per connection, handled by a fixed
+// number of worker threads so a burst of connections queues rather than spawning a thread each, with the body sized by
+// Content-Length - chunked bodies are turned away. Runs until interrupted.
+pub fn serve(args: &[String]) -> Result<()> {
+    let mut host = "127.0.0.1".to_string();
+    let mut port = 8080u16;
+    let mut max_body = DEFAULT_MAX_BODY;
+    let mut workers = thread::available_parallelism().map_or(1, |n| n.get());
+    let mut args = args.iter().map(String::as_str);
+    while let Some(arg) = args.next() {
+        match arg {
+            "--host" => {
+                host = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--host requires an address"))?
+                    .to_string();
+            }
+            "--port" => {
+                port = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--port requires a number"))?
+                    .parse()
+                    .context("--port requires a number")?;
+            }
+            "--max-body" => {
+                max_body = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--max-body requires a number of bytes"))?
+                    .parse()
+                    .context("--max-body requires a number of bytes")?;
+            }
+            "--workers" => {
+                let n = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--workers requires a number"))?;
+                workers = match n.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => bail!("invalid --workers value '{n}'"),
+                };
+            }
+            _ => bail!(
+                "usage: wasm-lang-inference serve [--host <address>] [--port <port>] [--max-body <bytes>] [--workers <n>]"
+            ),
+        }
+    }
+
+    let listener = TcpListener::bind((host.as_str(), port))
+        .with_context(|| format!("failed to listen on {host}:{port}"))?;
+    eprintln!("listening on http://{}", listener.local_addr()?);
+    // each worker accepts its own connections, so at most `workers` are handled at once and the rest wait in the
+    // listen backlog
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                for stream in listener.incoming() {
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            eprintln!("failed to accept a connection: {e}");
+                            continue;
+                        }
+                    };
+                    if let Err(e) = handle(stream, max_body) {
+                        eprintln!("failed to handle a request: {e}");
+                    }
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+struct Response {
+    status: &'static str,
+    body: Json,
+}
+
+fn error(status: &'static str, message: &str) -> Response {
+    Response {
+        status,
+        body: Json::object([("error", message.into())]),
+    }
+}
+
+fn handle(stream: TcpStream, max_body: usize) -> Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let response = respond(&mut reader, max_body);
+    let body = response.body.to_string();
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        response.status,
+        body.len()
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn respond(reader: &mut impl BufRead, max_body: usize) -> Response {
+    let (method, path, content_length) = match read_head(reader) {
+        Ok(head) => head,
+        Err(e) => return error("400 Bad Request", &e.to_string()),
+    };
+    match (method.as_str(), path.as_str()) {
+        ("GET", "/health") => Response {
+            status: "200 OK",
+            body: Json::object([("status", "ok".into())]),
+        },
+        ("POST", "/classify") => {
+            let length = match content_length {
+                Some(length) => length,
+                None => return error("411 Length Required", "the request needs a Content-Length"),
+            };
+            if length > max_body {
+                return error(
+                    "413 Payload Too Large",
+                    &format!("the body is over the limit of {max_body} bytes"),
+                );
+            }
+            // read as it arrives, rather than allocating the whole Content-Length up front
+            let mut buf = vec![];
+            if let Err(e) = reader.take(length as u64).read_to_end(&mut buf) {
+                return error("400 Bad Request", &format!("failed to read the body: {e}"));
+            }
+            if buf.len() < length {
+                return error(
+                    "400 Bad Request",
+                    "the body is shorter than its Content-Length",
+                );
+            }
+            classify_body(&buf)
+        }
+        (_, "/health" | "/classify") => error("405 Method Not Allowed", "method not allowed"),
+        _ => error("404 Not Found", "not found"),
+    }
+}
+
+// the request method, path and Content-Length, if there is one
+fn read_head(reader: &mut impl BufRead) -> Result<(String, String, Option<usize>)> {
+    let mut head = reader.take(MAX_HEAD as u64);
+    let mut line = String::new();
+    head.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(path), Some(version)) if version.starts_with("HTTP/1.") => {
+            (method.to_string(), path.to_string())
+        }
+        _ => bail!("malformed request line"),
+    };
+    let mut content_length = None;
+    loop {
+        line.clear();
+        if head.read_line(&mut line)? == 0 {
+            bail!("the request headers are incomplete, or too long");
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| anyhow!("malformed header '{header}'"))?;
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = Some(value.trim().parse().context("malformed Content-Length")?);
+        }
+        if name.eq_ignore_ascii_case("transfer-encoding") {
+            bail!("chunked bodies aren't supported, send a Content-Length instead");
+        }
+    }
+    Ok((method, path, content_length))
+}
+
+fn classify_body(buf: &[u8]) -> Response {
+    if !buf.starts_with(b"\0asm") {
+        return error("400 Bad Request", "the body isn't a wasm module");
+    }
+    let module = match parse(buf) {
+        Ok(module) => module,
+        Err(e) => return error("400 Bad Request", &e.to_string()),
+    };
+    if module.is_empty() {
+        return error(
+            "400 Bad Request",
+            "the body is too short to be a wasm module",
+        );
+    }
+    let classification = classify(&module);
+    Response {
+        status: "200 OK",
+        body: Json::object([
+            ("hash", sha256_hex(buf).into()),
+            ("language", classification.language.name().into()),
+            (
+                "js_binding",
+                classification.js_binding.map_or(Json::Null, Json::from),
+            ),
+            (
+                "runnability",
+                classification.runnability.map_or(Json::Null, Json::from),
+            ),
+            (
+                "hints",
+                Json::object(
+                    classification
+                        .hints
+                        .iter()
+                        .map(|(k, v)| (*k, v.as_str().into())),
+                ),
+            ),
+            (
+                "features",
+                Json::Array(module.features().iter().map(|f| (*f).into()).collect()),
+            ),
+            (
+                "min_feature_level",
+                min_feature_level(&module.features()).into(),
+            ),
+            (
+                "rule",
+                classification
+                    .rule
+                    .map_or(Json::Null, |rule| rule.id.into()),
+            ),
+            (
+                "confidence",
+                Json::Number((classification.confidence as f64 * 100.0).round() / 100.0),
+            ),
+            ("size", buf.len().into()),
+            ("partial", module.partial.into()),
+            ("oversized", module.oversized.into()),
+            ("stripped", module.is_stripped().into()),
+        ]),
+    }
+}
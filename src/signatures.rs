@@ -0,0 +1,173 @@
+// Additional detectors described as data rather than code, for community-contributed signatures of languages the
+// built-in rules don't know well enough. signatures/long-tail.json is an example.
+use crate::json::Json;
+use crate::{Language, WasmModule};
+use anyhow::{anyhow, bail, Context, Result};
+use std::fs;
+use std::path::Path;
+
+// The patterns a signature matches on. Every pattern given must match, so a signature can be as specific as it needs
+// to be, but it must give at least one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Patterns {
+    // the module of any import, e.g. `javascript_kit`
+    pub import_module: Option<String>,
+    // the name of any export, e.g. `swjs_*`
+    pub export_name: Option<String>,
+    // the bytes of any data segment, e.g. `*~lib/rt/*` - names are matched whole, so this needs a `*` either side to
+    // find a string among the rest of the segment
+    pub data_string: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature {
+    pub id: String,
+    pub language: Language,
+    pub confidence: f32,
+    pub patterns: Patterns,
+}
+
+const KEYS: [&str; 6] = [
+    "id",
+    "language",
+    "confidence",
+    "import_module",
+    "export_name",
+    "data_string",
+];
+
+// Glob patterns, rather than regular expressions: `*` matches any run of characters (including none) and `?` any
+// single character, everything else only itself
+fn glob_matches(pattern: &[u8], text: &[u8]) -> bool {
+    // the position in each to return to on a mismatch, just after the last `*` and the text it was expanded to cover
+    let (mut p, mut t) = (0, 0);
+    let mut retry = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                retry = Some((p + 1, t));
+                p += 1;
+            }
+            Some(c) if *c == b'?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match retry {
+                Some((after_star, covered)) => {
+                    p = after_star;
+                    t = covered + 1;
+                    retry = Some((after_star, covered + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == b'*')
+}
+
+impl Signature {
+    pub fn matches(&self, module: &WasmModule) -> bool {
+        let patterns = &self.patterns;
+        patterns.import_module.as_ref().is_none_or(|pattern| {
+            module
+                .import_modules
+                .iter()
+                .any(|m| glob_matches(pattern.as_bytes(), m.as_bytes()))
+        }) && patterns.export_name.as_ref().is_none_or(|pattern| {
+            module.any_exports_match(|e| glob_matches(pattern.as_bytes(), e.name.as_bytes()))
+        }) && patterns.data_string.as_ref().is_none_or(|pattern| {
+            module
+                .data
+                .iter()
+                .any(|data| glob_matches(pattern.as_bytes(), data))
+        })
+    }
+
+    fn from_json(json: &Json) -> Result<Signature> {
+        let entries = match json {
+            Json::Object(entries) => entries,
+            _ => bail!("expected an object"),
+        };
+        if let Some((key, _)) = entries
+            .iter()
+            .find(|(key, _)| !KEYS.contains(&key.as_str()))
+        {
+            bail!("unknown key '{key}', expected one of: {}", KEYS.join(", "));
+        }
+        let string = |key: &str| -> Result<Option<String>> {
+            match json.get(key) {
+                None => Ok(None),
+                Some(Json::String(s)) if !s.is_empty() => Ok(Some(s.clone())),
+                Some(_) => bail!("'{key}' must be a non-empty string"),
+            }
+        };
+        let id = string("id")?.ok_or_else(|| anyhow!("missing 'id'"))?;
+        let language = string("language")?
+            .ok_or_else(|| anyhow!("{id}: missing 'language'"))?
+            .parse()
+            .with_context(|| id.clone())?;
+        let confidence = match json.get("confidence") {
+            Some(Json::Number(n)) if (0.0..=1.0).contains(n) => *n as f32,
+            Some(_) => bail!("{id}: 'confidence' must be a number from 0 to 1"),
+            None => bail!("{id}: missing 'confidence'"),
+        };
+        let patterns = Patterns {
+            import_module: string("import_module").with_context(|| id.clone())?,
+            export_name: string("export_name").with_context(|| id.clone())?,
+            data_string: string("data_string").with_context(|| id.clone())?,
+        };
+        if patterns == Patterns::default() {
+            bail!("{id}: needs at least one of 'import_module', 'export_name' or 'data_string'");
+        }
+        Ok(Signature {
+            id,
+            language,
+            confidence,
+            patterns,
+        })
+    }
+}
+
+// The signatures in a file, in the order they are tried in
+#[derive(Debug, Clone, Default)]
+pub struct Signatures {
+    signatures: Vec<Signature>,
+}
+
+impl Signatures {
+    // Adds the signatures in the file to the end of the list, failing on the first one that isn't valid - a file with
+    // a typo in it would otherwise silently match nothing
+    pub fn load(&mut self, path: &Path) -> Result<()> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let json: Json = text
+            .parse()
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        let entries = json
+            .get("signatures")
+            .and_then(Json::as_array)
+            .ok_or_else(|| anyhow!("{}: expected a 'signatures' array", path.display()))?;
+        for (index, entry) in entries.iter().enumerate() {
+            let signature = Signature::from_json(entry).with_context(|| {
+                format!("{}: invalid signature at index {index}", path.display())
+            })?;
+            if self.signatures.iter().any(|s| s.id == signature.id) {
+                bail!(
+                    "{}: there is already a signature with the id '{}'",
+                    path.display(),
+                    signature.id
+                );
+            }
+            self.signatures.push(signature);
+        }
+        Ok(())
+    }
+
+    pub fn signatures(&self) -> &[Signature] {
+        &self.signatures
+    }
+
+    pub fn matching_signature(&self, module: &WasmModule) -> Option<&Signature> {
+        self.signatures.iter().find(|s| s.matches(module))
+    }
+}
@@ -0,0 +1,219 @@
+// Helpers for testing detectors, here and in crates that build on this one: a builder for small modules with just the
+// imports, exports and sections a detector looks at, and an assertion that explains which rules matched when a module
+// is classified as the wrong language. The builder only emits what the parser reads - function bodies are a lone
+// `unreachable`, so the modules are valid but do nothing.
+
+use crate::{classify, format_hints, matching_rules, parse_wasm, Language, Type};
+
+fn leb(mut n: usize, out: &mut Vec<u8>) {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn name(name: &str, out: &mut Vec<u8>) {
+    leb(name.len(), out);
+    out.extend_from_slice(name.as_bytes());
+}
+
+fn value_type(ty: Type) -> u8 {
+    match ty {
+        Type::I32 => 0x7f,
+        Type::I64 => 0x7e,
+        Type::F32 => 0x7d,
+        Type::F64 => 0x7c,
+        Type::V128 => 0x7b,
+        Type::FuncRef => 0x70,
+        Type::ExternRef => 0x6f,
+    }
+}
+
+fn section(id: u8, entries: &[Vec<u8>], out: &mut Vec<u8>) {
+    if entries.is_empty() {
+        return;
+    }
+    let mut contents = vec![];
+    leb(entries.len(), &mut contents);
+    for entry in entries {
+        contents.extend_from_slice(entry);
+    }
+    out.push(id);
+    leb(contents.len(), out);
+    out.extend(contents);
+}
+
+// A core module under construction, e.g.
+// `ModuleBuilder::new().import_func("env", "emscripten_resize_heap", &[Type::I32], &[Type::I32]).build()`
+#[derive(Default)]
+pub struct ModuleBuilder {
+    types: Vec<Vec<u8>>,
+    imports: Vec<Vec<u8>>,
+    functions: Vec<Vec<u8>>,
+    memories: Vec<Vec<u8>>,
+    exports: Vec<Vec<u8>>,
+    data: Vec<Vec<u8>>,
+    custom_sections: Vec<(String, Vec<u8>)>,
+    imported_functions: usize,
+    has_memory: bool,
+}
+
+impl ModuleBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn func_type(&mut self, params: &[Type], results: &[Type]) -> usize {
+        let mut ty = vec![0x60];
+        leb(params.len(), &mut ty);
+        ty.extend(params.iter().map(|ty| value_type(*ty)));
+        leb(results.len(), &mut ty);
+        ty.extend(results.iter().map(|ty| value_type(*ty)));
+        self.types.push(ty);
+        self.types.len() - 1
+    }
+
+    pub fn import_func(
+        mut self,
+        module: &str,
+        field: &str,
+        params: &[Type],
+        results: &[Type],
+    ) -> Self {
+        assert!(
+            self.functions.is_empty(),
+            "functions must be imported before any are defined, as the imports come first in the index space"
+        );
+        let ty = self.func_type(params, results);
+        let mut import = vec![];
+        name(module, &mut import);
+        name(field, &mut import);
+        import.push(0x00);
+        leb(ty, &mut import);
+        self.imports.push(import);
+        self.imported_functions += 1;
+        self
+    }
+
+    // an imported memory of `pages` 64KiB pages, with no maximum
+    pub fn import_memory(mut self, module: &str, field: &str, pages: usize) -> Self {
+        let mut import = vec![];
+        name(module, &mut import);
+        name(field, &mut import);
+        import.extend([0x02, 0x00]);
+        leb(pages, &mut import);
+        self.imports.push(import);
+        self.has_memory = true;
+        self
+    }
+
+    // a memory of `pages` 64KiB pages, exported as `export` if given
+    pub fn memory(mut self, pages: usize, export: Option<&str>) -> Self {
+        let mut memory = vec![0x00];
+        leb(pages, &mut memory);
+        self.memories.push(memory);
+        if let Some(export) = export {
+            let mut entry = vec![];
+            name(export, &mut entry);
+            entry.push(0x02);
+            leb(self.memories.len() - 1, &mut entry);
+            self.exports.push(entry);
+        }
+        self.has_memory = true;
+        self
+    }
+
+    // a function, exported as `export` if given - most detectors only see the names of unexported functions in the
+    // name section, which can be added with `custom`
+    pub fn func(mut self, export: Option<&str>, params: &[Type], results: &[Type]) -> Self {
+        let ty = self.func_type(params, results);
+        let mut function = vec![];
+        leb(ty, &mut function);
+        self.functions.push(function);
+        if let Some(export) = export {
+            let mut entry = vec![];
+            name(export, &mut entry);
+            entry.push(0x00);
+            leb(
+                self.imported_functions + self.functions.len() - 1,
+                &mut entry,
+            );
+            self.exports.push(entry);
+        }
+        self
+    }
+
+    // an active data segment at address 0 of the first memory, which has to be added first
+    pub fn data(mut self, bytes: &[u8]) -> Self {
+        assert!(self.has_memory, "data needs a memory to be placed in");
+        let mut segment = vec![0x00, 0x41, 0x00, 0x0b];
+        leb(bytes.len(), &mut segment);
+        segment.extend_from_slice(bytes);
+        self.data.push(segment);
+        self
+    }
+
+    // a custom section, written after all the others
+    pub fn custom(mut self, name: &str, contents: &[u8]) -> Self {
+        self.custom_sections
+            .push((name.to_string(), contents.to_vec()));
+        self
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        let mut module = b"\0asm\x01\0\0\0".to_vec();
+        section(1, &self.types, &mut module);
+        section(2, &self.imports, &mut module);
+        section(3, &self.functions, &mut module);
+        section(5, &self.memories, &mut module);
+        section(7, &self.exports, &mut module);
+        // no locals, and `unreachable`, which satisfies any result type
+        let bodies = vec![vec![0x03, 0x00, 0x00, 0x0b]; self.functions.len()];
+        section(10, &bodies, &mut module);
+        section(11, &self.data, &mut module);
+        for (section_name, contents) in &self.custom_sections {
+            let mut payload = vec![];
+            name(section_name, &mut payload);
+            payload.extend_from_slice(contents);
+            module.push(0x00);
+            leb(payload.len(), &mut module);
+            module.extend(payload);
+        }
+        module
+    }
+}
+
+// Classifies `wasm` and panics unless it is `expected`, listing every rule that matched, so a failure shows whether the
+// detector under test didn't fire or an earlier rule got there first
+#[track_caller]
+pub fn assert_classification(wasm: &[u8], expected: Language) {
+    let module = parse_wasm(wasm);
+    let classification = classify(&module);
+    if classification.language == expected {
+        return;
+    }
+    let decided_by = classification
+        .rule
+        .map_or("no rule matched".to_string(), |rule| {
+            format!("decided by {}", rule.id)
+        });
+    let matched: Vec<String> = matching_rules(&module)
+        .iter()
+        .map(|rule| format!("  {} ({:?}, {})", rule.id, rule.language, rule.confidence))
+        .collect();
+    panic!(
+        "expected {expected:?}, but the module was classified as {:?} ({decided_by})\nmatching rules, in the order they are tried:\n{}\nhints: {}",
+        classification.language,
+        if matched.is_empty() {
+            "  none".to_string()
+        } else {
+            matched.join("\n")
+        },
+        format_hints(&classification.hints)
+    );
+}
@@ -0,0 +1,173 @@
+// The spec test suite's .wast scripts interleave modules with assertions and other commands. Binary modules are
+// written out as strings, `(module $name binary "\00asm" "\01\00\00\00" ...)`, and can be classified like any other.
+// Text modules would need assembling first, which is outside the scope of this tool, so they are only counted.
+#[derive(Default)]
+pub struct Script {
+    // in the order they appear in the script, with the ordinal of each among the script's modules
+    pub binary_modules: Vec<(usize, Vec<u8>)>,
+    pub text_modules: usize,
+}
+
+enum Token {
+    Open,
+    Close,
+    Atom(String),
+    Str(Vec<u8>),
+}
+
+// Only the top level of the script is considered, so modules nested in assertions (e.g. `assert_invalid`), which are
+// broken on purpose, are skipped along with the rest of the command
+pub fn read_script(script: &str) -> Script {
+    let tokens = tokenize(script);
+    let mut result = Script::default();
+    let mut depth = 0;
+    let mut index = 0;
+    let mut modules = 0;
+    while index < tokens.len() {
+        match &tokens[index] {
+            Token::Open => {
+                if depth == 0
+                    && matches!(tokens.get(index + 1), Some(Token::Atom(a)) if a == "module")
+                {
+                    let (module, end) = read_module(&tokens, index + 2);
+                    match module {
+                        Some(bytes) => result.binary_modules.push((modules, bytes)),
+                        None => result.text_modules += 1,
+                    }
+                    modules += 1;
+                    index = end;
+                    continue;
+                }
+                depth += 1;
+            }
+            Token::Close => depth -= 1,
+            _ => {}
+        }
+        index += 1;
+    }
+    result
+}
+
+// Reads the body of a `(module ...)` form, starting after the `module` keyword. Returns the binary module, if it is
+// one, and the index of the token following the form's closing bracket.
+fn read_module(tokens: &[Token], mut index: usize) -> (Option<Vec<u8>>, usize) {
+    let mut binary = false;
+    let mut bytes = vec![];
+    let mut depth = 1;
+    while let Some(token) = tokens.get(index) {
+        index += 1;
+        match token {
+            Token::Open => depth += 1,
+            Token::Close => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            Token::Atom(atom) if depth == 1 && atom == "binary" => binary = true,
+            Token::Str(s) if depth == 1 && binary => bytes.extend_from_slice(s),
+            _ => {}
+        }
+    }
+    (binary.then_some(bytes), index)
+}
+
+fn tokenize(script: &str) -> Vec<Token> {
+    let chars: Vec<char> = script.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            ';' if chars.get(i + 1) == Some(&';') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '(' if chars.get(i + 1) == Some(&';') => {
+                // block comments nest
+                let mut depth = 0;
+                while i < chars.len() {
+                    if chars[i] == '(' && chars.get(i + 1) == Some(&';') {
+                        depth += 1;
+                        i += 2;
+                    } else if chars[i] == ';' && chars.get(i + 1) == Some(&')') {
+                        depth -= 1;
+                        i += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        i += 1;
+                    }
+                }
+                continue;
+            }
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '"' => {
+                let (s, end) = read_string(&chars, i + 1);
+                tokens.push(Token::Str(s));
+                i = end;
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() && !"()\";".contains(chars[i]) {
+                    i += 1;
+                }
+                tokens.push(Token::Atom(chars[start..i].iter().collect()));
+                continue;
+            }
+        }
+        i += 1;
+    }
+    tokens
+}
+
+// Decodes a string literal starting after its opening quote, returning its bytes and the index of the closing quote.
+// Besides the usual escapes, `\hh` is a raw byte, which is how binary modules are written.
+fn read_string(chars: &[char], mut i: usize) -> (Vec<u8>, usize) {
+    let mut bytes = vec![];
+    while i < chars.len() && chars[i] != '"' {
+        if chars[i] != '\\' {
+            let mut utf8 = [0; 4];
+            bytes.extend_from_slice(chars[i].encode_utf8(&mut utf8).as_bytes());
+            i += 1;
+            continue;
+        }
+        let hex = |c: Option<&char>| c.and_then(|c| c.to_digit(16));
+        match chars.get(i + 1) {
+            Some('t') => bytes.push(b'\t'),
+            Some('n') => bytes.push(b'\n'),
+            Some('r') => bytes.push(b'\r'),
+            Some('u') => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|&c| c == '}')
+                    .map_or(chars.len(), |p| i + p);
+                let digits: String = chars[(i + 3).min(end)..end].iter().collect();
+                if let Some(c) = u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                {
+                    let mut utf8 = [0; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut utf8).as_bytes());
+                }
+                i = end + 1;
+                continue;
+            }
+            Some(&c) => match (hex(Some(&c)), hex(chars.get(i + 2))) {
+                (Some(high), Some(low)) => {
+                    bytes.push((high * 16 + low) as u8);
+                    i += 3;
+                    continue;
+                }
+                // \" \' and \\
+                _ => bytes.push(c as u8),
+            },
+            None => {}
+        }
+        i += 2;
+    }
+    (bytes, i)
+}
@@ -0,0 +1,100 @@
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use wasm_lang_inference::{classify, format_hints, parse_wasm, tally, Language};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// what a file looked like when it was last seen, changes to which mean it needs reclassifying
+type FileState = (Option<SystemTime>, u64);
+
+// Keeps classifying the modules in a directory as they are added, changed and removed, e.g. while iterating on a
+// build. The directory is polled, as the `notify` crate isn't among the dependencies, and a changed file is only
+// classified once it has stayed the same for a whole interval, so a module that is still being written out is
+// classified once it is complete rather than at every write. Files that aren't wasm, such as the JS glue and source
+// maps alongside the modules, are left out, as in a scan. Runs until interrupted.
+pub fn watch(args: &[String]) -> Result<()> {
+    let dir = match args {
+        [] => PathBuf::from("./wasm"),
+        [dir] => PathBuf::from(dir),
+        _ => bail!("usage: wasm-lang-inference --watch [directory]"),
+    };
+
+    // None for the files that turned out not to be modules, so they aren't read again until they change
+    let mut classified: BTreeMap<PathBuf, (FileState, Option<Language>)> = BTreeMap::new();
+    let mut pending: BTreeMap<PathBuf, FileState> = BTreeMap::new();
+    println!("watching {}", dir.display());
+    loop {
+        let mut current: BTreeMap<PathBuf, FileState> = BTreeMap::new();
+        // a missing directory, e.g. while a build cleans it out and recreates it, is as good as an empty one
+        for entry in fs::read_dir(&dir).into_iter().flatten() {
+            // files can disappear between being listed and being looked at, which is just another change
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(_) => continue,
+            };
+            if let Ok(metadata) = entry.metadata() {
+                if metadata.is_file() {
+                    current.insert(entry.path(), (metadata.modified().ok(), metadata.len()));
+                }
+            }
+        }
+
+        let mut changed = false;
+        for (path, state) in &current {
+            if classified.get(path).is_some_and(|(s, _)| s == state) {
+                continue;
+            }
+            if pending.get(path) != Some(state) {
+                pending.insert(path.clone(), *state);
+                continue;
+            }
+            pending.remove(path);
+            let buf = match fs::read(path) {
+                Ok(buf) => buf,
+                Err(_) => continue,
+            };
+            let module = parse_wasm(&buf);
+            let is_wasm = module.error.is_none()
+                && (!buf.is_empty() || path.extension().is_some_and(|ext| ext == "wasm"));
+            if !is_wasm {
+                classified.insert(path.clone(), (*state, None));
+                continue;
+            }
+            let classification = classify(&module);
+            let mut line = format!("{:?}, {}", classification.language, path.display());
+            if !classification.hints.is_empty() {
+                line += &format!(", {}", format_hints(&classification.hints));
+            }
+            println!("{line}");
+            classified.insert(path.clone(), (*state, Some(classification.language)));
+            changed = true;
+        }
+
+        let removed: Vec<PathBuf> = classified
+            .keys()
+            .filter(|path| !current.contains_key(*path))
+            .cloned()
+            .collect();
+        for path in removed {
+            if let Some((_, Some(_))) = classified.remove(&path) {
+                println!("removed, {}", path.display());
+                changed = true;
+            }
+        }
+        pending.retain(|path, _| current.contains_key(path));
+
+        if changed {
+            let counts = tally(
+                classified
+                    .values()
+                    .filter_map(|(_, language)| language.as_ref()),
+            );
+            println!("{counts:?}");
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
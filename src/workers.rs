@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+
+// Maps `f` over `items` on up to `workers` threads. `emit` sees each result in the original order, as soon as it and
+// every result ahead of it are ready, so that streamed output is the same whatever the number of workers. With a
+// single worker everything runs on the calling thread.
+pub fn map_ordered<T, R, F, E>(items: Vec<T>, workers: usize, f: F, mut emit: E) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+    E: FnMut(&R),
+{
+    if workers <= 1 {
+        return items
+            .into_iter()
+            .map(|item| {
+                let result = f(item);
+                emit(&result);
+                result
+            })
+            .collect();
+    }
+
+    let count = items.len();
+    let items: Vec<_> = items
+        .into_iter()
+        .map(|item| Mutex::new(Some(item)))
+        .collect();
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<Option<R>> = (0..count).map(|_| None).collect();
+
+    thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..workers.min(count) {
+            let sender = sender.clone();
+            let (items, next, f) = (&items, &next, &f);
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                if index >= count {
+                    break;
+                }
+                let item = items[index].lock().unwrap().take().unwrap();
+                if sender.send((index, f(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut emitted = 0;
+        for (index, result) in receiver {
+            results[index] = Some(result);
+            while let Some(Some(result)) = results.get(emitted) {
+                emit(result);
+                emitted += 1;
+            }
+        }
+    });
+
+    results.into_iter().map(Option::unwrap).collect()
+}
+
+// Counts the distinct results of mapping `f` over `items` on up to `workers` threads, without holding on to the results
+// themselves. Each thread keeps its own counts, which are merged once they have all finished.
+pub fn count<T, K, F>(items: Vec<T>, workers: usize, f: F) -> BTreeMap<K, usize>
+where
+    T: Send,
+    K: Ord + Send,
+    F: Fn(T) -> K + Sync,
+{
+    let mut counts = BTreeMap::new();
+    if workers <= 1 {
+        for item in items {
+            *counts.entry(f(item)).or_insert(0) += 1;
+        }
+        return counts;
+    }
+
+    let count = items.len();
+    let items: Vec<_> = items
+        .into_iter()
+        .map(|item| Mutex::new(Some(item)))
+        .collect();
+    let next = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        let threads: Vec<_> = (0..workers.min(count))
+            .map(|_| {
+                let (items, next, f) = (&items, &next, &f);
+                scope.spawn(move || {
+                    let mut counts = BTreeMap::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        if index >= count {
+                            break counts;
+                        }
+                        let item = items[index].lock().unwrap().take().unwrap();
+                        *counts.entry(f(item)).or_insert(0) += 1;
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            for (key, n) in thread.join().unwrap() {
+                *counts.entry(key).or_insert(0) += n;
+            }
+        }
+    });
+    counts
+}
@@ -0,0 +1,43 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+// a baseline written with --update-baseline passes until the counts move by more than the tolerance
+#[test]
+fn scans_are_compared_to_the_baseline() {
+    let dir = env::temp_dir().join(format!(
+        "wasm-lang-inference-baseline-{}",
+        std::process::id()
+    ));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    for name in ["go.wasm", "rust-wasi.wasm", "rust-wasm-bindgen.wasm"] {
+        fs::copy(fixtures.join(name), wasm.join(name)).unwrap();
+    }
+
+    let run = |args: &[&str]| -> Output {
+        Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+            .args(["--baseline", "baseline.json"])
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap()
+    };
+    assert!(run(&["--update-baseline"]).status.success());
+    let baseline = fs::read_to_string(dir.join("baseline.json")).unwrap();
+    assert_eq!(baseline, "{\"rust\":2,\"go\":1}\n");
+    assert!(run(&[]).status.success());
+    assert!(run(&["--count-only"]).status.success());
+
+    fs::write(dir.join("baseline.json"), "{\"rust\": 3, \"go\": 1}").unwrap();
+    let drifted = run(&[]);
+    let lenient = run(&["--baseline-tolerance", "50"]);
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(!drifted.status.success());
+    let stderr = String::from_utf8(drifted.stderr).unwrap();
+    assert!(stderr.contains("  rust: 3 -> 2 (-1)"), "{stderr}");
+    assert!(lenient.status.success());
+}
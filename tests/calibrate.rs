@@ -0,0 +1,46 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// the rules are scored against the producers section, on the modules that have one, with it hidden from them
+#[test]
+fn rules_are_calibrated_against_the_producers_section() {
+    let dir = env::temp_dir().join(format!(
+        "wasm-lang-inference-calibrate-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    for name in [
+        "c-env-abort.wasm",
+        "rust-emscripten.wasm",
+        "rust-wasi.wasm",
+        "unknown.wasm",
+        "go.wasm",
+    ] {
+        fs::copy(fixtures.join(name), dir.join(name)).unwrap();
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .arg("--calibrate")
+        .arg(&dir)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(
+        lines[0].starts_with("calibrated against 3 modules"),
+        "{stdout}"
+    );
+    // rust-emscripten.wasm is Rust by its producers, which rust.toolchain gets right and emscripten.import doesn't
+    let row = |id: &str| {
+        let row = lines.iter().find(|line| line.starts_with(id)).unwrap();
+        row.split_whitespace().skip(1).take(3).collect::<Vec<_>>()
+    };
+    assert_eq!(row("rust.toolchain"), ["1", "1", "100%"], "{stdout}");
+    assert_eq!(row("emscripten.import"), ["1", "0", "0%"], "{stdout}");
+}
@@ -0,0 +1,67 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// A scan resumed from a checkpoint only classifies the files that weren't recorded, and counts the rest as recorded
+#[test]
+fn resumed_scan_skips_checkpointed_files() {
+    let dir = env::temp_dir().join(format!(
+        "wasm-lang-inference-checkpoint-{}",
+        std::process::id()
+    ));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    for name in ["go.wasm", "rust-wasm-bindgen.wasm", "unknown.wasm"] {
+        fs::copy(fixtures.join(name), wasm.join(name)).unwrap();
+    }
+    let run = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+            .args(["--checkpoint", "scan.checkpoint"])
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    run(&[]);
+    let checkpoint = fs::read_to_string(dir.join("scan.checkpoint")).unwrap();
+    assert_eq!(checkpoint.lines().count(), 4, "{checkpoint}");
+    assert!(checkpoint.contains("go\t./wasm/go.wasm"), "{checkpoint}");
+    assert!(!dir.join("scan.checkpoint.tmp").exists());
+
+    fs::copy(
+        fixtures.join("emscripten.wasm"),
+        wasm.join("emscripten.wasm"),
+    )
+    .unwrap();
+    let stdout = run(&[]);
+    let counts = run(&["--count-only"]);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(
+        stdout.contains("Emscripten, ./wasm/emscripten.wasm"),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("./wasm/go.wasm"), "{stdout}");
+    assert!(
+        stdout.contains("3 files were already classified"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("{Rust: 1, Emscripten: 1, Unknown: 1, Go: 1}"),
+        "{stdout}"
+    );
+    assert!(
+        counts.contains("{Rust: 1, Emscripten: 1, Unknown: 1, Go: 1}"),
+        "{counts}"
+    );
+    assert!(
+        counts.contains("4 files were already classified"),
+        "{counts}"
+    );
+}
@@ -0,0 +1,106 @@
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use wasm_lang_inference::{parse_wasm, Classifier, Language, ParseError, ParseOptions};
+
+fn fixture(name: &str) -> Vec<u8> {
+    fs::read(
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name),
+    )
+    .unwrap()
+}
+
+#[test]
+fn classifier_keeps_a_running_summary() {
+    let mut classifier = Classifier::new(ParseOptions::default());
+    assert_eq!(
+        classifier.classify(&fixture("go.wasm")).language,
+        Language::Go
+    );
+    classifier.classify(&fixture("rust-wasm-bindgen.wasm"));
+    classifier.classify(&fixture("rust-truncated.wasm"));
+    classifier.classify(&fixture("unknown.wasm"));
+
+    let summary = classifier.summary();
+    assert_eq!(summary.total, 4);
+    assert_eq!(summary.partial, 1);
+    assert_eq!(summary.counts[&Language::Rust], 2);
+    assert_eq!(summary.unclassified_percent(), 25.0);
+}
+
+// the lists reused between modules mustn't carry anything over from one to the next
+#[test]
+fn classify_many_matches_classifying_one_at_a_time() {
+    let buffers: Vec<_> = [
+        "emscripten-multi-table.wasm",
+        "go.wasm",
+        "unknown.wasm",
+        "rust-wasm-bindgen.wasm",
+    ]
+    .into_iter()
+    .map(fixture)
+    .collect();
+
+    let mut one_at_a_time = Classifier::default();
+    let expected: Vec<_> = buffers
+        .iter()
+        .map(|buf| one_at_a_time.classify(buf))
+        .map(|c| (c.language, c.hints))
+        .collect();
+    let mut batch = Classifier::default();
+    let actual: Vec<_> = batch
+        .classify_many(buffers.iter().map(Vec::as_slice))
+        .map(|c| (c.language, c.hints))
+        .collect();
+    assert_eq!(actual, expected);
+    assert_eq!(batch.summary(), one_at_a_time.summary());
+}
+
+// a core module header, but with a version this tool doesn't know how to read
+#[test]
+fn unsupported_versions_are_reported_distinctly() {
+    let buf = b"\0asm\x02\0\0\0";
+    assert_eq!(
+        parse_wasm(buf).error,
+        Some(ParseError::UnsupportedVersion(2))
+    );
+
+    let mut classifier = Classifier::default();
+    assert_eq!(classifier.classify(buf).language, Language::Unknown);
+    let summary = classifier.summary();
+    assert_eq!(summary.unsupported_version, 1);
+    assert_eq!(summary.partial, 0);
+
+    // text isn't a module with an unknown version, it isn't wasm at all
+    classifier.classify(b"hello world, this is js");
+    let summary = classifier.summary();
+    assert_eq!(summary.unsupported_version, 1);
+    assert_eq!(summary.not_wasm, 1);
+}
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn classifier_can_be_shared_between_threads() {
+    assert_send_sync::<Classifier>();
+
+    let classifier = Arc::new(Mutex::new(Classifier::default()));
+    let workers: Vec<_> = ["go.wasm", "emscripten.wasm", "julia.wasm"]
+        .into_iter()
+        .map(|name| {
+            let classifier = Arc::clone(&classifier);
+            thread::spawn(move || classifier.lock().unwrap().classify(&fixture(name)))
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    assert_eq!(classifier.lock().unwrap().summary().total, 3);
+
+    let mut combined = Classifier::default();
+    combined.merge(&classifier.lock().unwrap().summary());
+    assert_eq!(combined.summary(), classifier.lock().unwrap().summary());
+}
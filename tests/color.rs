@@ -0,0 +1,40 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// Output to a pipe is only coloured when asked for, and the machine-readable formats never are
+#[test]
+fn language_names_are_only_coloured_when_asked_for() {
+    let dir = env::temp_dir().join(format!("wasm-lang-inference-color-{}", std::process::id()));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    fs::copy(fixtures.join("go.wasm"), wasm.join("go.wasm")).unwrap();
+
+    let run = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let always = run(&["--color", "always"]);
+    let auto = run(&["--color", "auto"]);
+    let never = run(&["--color", "never"]);
+    let json = run(&["--color", "always", "--format", "ndjson"]);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(
+        always.starts_with("\x1b[36mGo\x1b[0m, ./wasm/go.wasm"),
+        "{always}"
+    );
+    assert!(always.contains("{\x1b[36mGo\x1b[0m: 1}"), "{always}");
+    assert!(!auto.contains('\x1b'), "{auto}");
+    assert!(never.starts_with("Go, ./wasm/go.wasm"), "{never}");
+    assert!(never.contains("{Go: 1}"), "{never}");
+    assert!(!json.contains('\x1b'), "{json}");
+}
@@ -0,0 +1,38 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// --count-only prints the same summary counts as a full scan, without the per-file lines
+#[test]
+fn count_only_matches_the_full_scan() {
+    let dir = env::temp_dir().join(format!("wasm-lang-inference-count-{}", std::process::id()));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    for entry in fs::read_dir(fixtures).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|ext| ext == "wasm") {
+            fs::copy(&path, wasm.join(path.file_name().unwrap())).unwrap();
+        }
+    }
+
+    let run = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let full = run(&["--workers", "2"]);
+    let counts = run(&["--workers", "2", "--count-only"]);
+    fs::remove_dir_all(&dir).unwrap();
+
+    let histogram = counts.lines().next().unwrap();
+    assert!(histogram.starts_with('{'), "{counts}");
+    assert!(full.lines().any(|line| line == histogram), "{full}");
+    assert!(!counts.contains("./wasm/"), "{counts}");
+}
@@ -0,0 +1,41 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn run(dir: &Path) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .arg("--distinct")
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// --distinct prints each language found once, sorted by name, and nothing else
+#[test]
+fn distinct_lists_each_language_once() {
+    let dir = env::temp_dir().join(format!(
+        "wasm-lang-inference-distinct-{}",
+        std::process::id()
+    ));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    assert_eq!(run(&dir), "");
+
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    for name in [
+        "go.wasm",
+        "julia.wasm",
+        "rust-wasi.wasm",
+        "rust-wasm-bindgen.wasm",
+    ] {
+        fs::copy(fixtures.join(name), wasm.join(name)).unwrap();
+    }
+    let languages = run(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(languages, "go\njulia\nrust\n");
+}
@@ -0,0 +1,66 @@
+use std::fs;
+use std::path::Path;
+use wasm_lang_inference::{classify, parse_wasm, DylinkInfo, Language};
+
+#[test]
+fn dylink_sections_are_read() {
+    let buf = fs::read(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/emscripten-dylink.wasm"),
+    )
+    .unwrap();
+    assert_eq!(
+        parse_wasm(&buf).dylink,
+        Some(DylinkInfo {
+            memory_size: 1024,
+            memory_alignment: 4,
+            table_size: 2,
+            table_alignment: 0,
+            needed: vec!["libz.so", "libpng.so"],
+        })
+    );
+}
+
+// the legacy `dylink` section has the same fields, without dylink.0's subsection headers
+#[test]
+fn legacy_dylink_sections_are_read() {
+    let mut section = vec![6];
+    section.extend(b"dylink");
+    section.extend([0x80, 0x08, 2, 8, 0, 1, 7]);
+    section.extend(b"libc.so");
+    let mut buf = b"\0asm\x01\0\0\0\0".to_vec();
+    buf.push(section.len() as u8);
+    buf.extend(section);
+
+    assert_eq!(
+        parse_wasm(&buf).dylink,
+        Some(DylinkInfo {
+            memory_size: 1024,
+            memory_alignment: 2,
+            table_size: 8,
+            table_alignment: 0,
+            needed: vec!["libc.so"],
+        })
+    );
+}
+
+// without a dylink section to go on, a side module still imports the main module's memory and table
+#[test]
+fn side_modules_import_the_main_modules_memory_and_table() {
+    let buf = fs::read(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/emscripten-side-imported.wasm"),
+    )
+    .unwrap();
+    let module = parse_wasm(&buf);
+    assert!(module.imports_memory && module.imports_table);
+    assert_eq!(module.dylink, None);
+    let classification = classify(&module);
+    assert_eq!(classification.language, Language::Emscripten);
+    assert_eq!(classification.rule.unwrap().id, "emscripten.side_module");
+
+    let buf = fs::read(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/emscripten-pthread-worker.wasm"),
+    )
+    .unwrap();
+    let module = parse_wasm(&buf);
+    assert!(module.imports_memory && !module.imports_table);
+}
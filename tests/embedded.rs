@@ -0,0 +1,26 @@
+use std::fs;
+use std::path::Path;
+use wasm_lang_inference::{extract_embedded, infer_language, parse_wasm, Language};
+
+const RUST_WASM_BINDGEN_BASE64: &str = "AGFzbQEAAAABCwJgAn9/AGABfwF/Ai8CA3diZxBfX3diZ19sb2dfMWQzYWUwAAADd2JnEF9fd2JpbmRnZW5fdGhyb3cAAAMDAgABBQMBABEHJgMGbWVtb3J5AgAFZ3JlZXQAAhFfX3diaW5kZ2VuX21hbGxvYwADCgkCAgALBABBAAs=";
+
+#[test]
+fn extracts_a_module_from_a_data_uri() {
+    let js = format!(
+        "const bytes = await fetch('data:application/wasm;base64,{RUST_WASM_BINDGEN_BASE64}');\n\
+         await WebAssembly.instantiateStreaming(bytes, imports);\n"
+    );
+    let expected = fs::read(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/rust-wasm-bindgen.wasm"),
+    )
+    .unwrap();
+
+    let module = extract_embedded(js.as_bytes()).unwrap();
+    assert_eq!(module, expected);
+    assert_eq!(infer_language(&parse_wasm(&module)), Language::Rust);
+}
+
+#[test]
+fn ignores_files_without_an_embedded_module() {
+    assert_eq!(extract_embedded(b"<!DOCTYPE html><p>Not Found</p>"), None);
+}
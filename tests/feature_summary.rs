@@ -0,0 +1,55 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// each feature is counted across the modules, with the code features only listed when the code is scanned
+#[test]
+fn features_are_tallied_across_modules() {
+    let dir = env::temp_dir().join(format!(
+        "wasm-lang-inference-feature-summary-{}",
+        std::process::id()
+    ));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    for name in [
+        "emscripten-multi-table.wasm",
+        "emscripten-pthread-worker.wasm",
+        "c-modern-features.wasm",
+        "go.wasm",
+    ] {
+        fs::copy(fixtures.join(name), wasm.join(name)).unwrap();
+    }
+
+    let run = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let summary = run(&["--feature-summary"]);
+    let scanned = run(&["--feature-summary", "--scan-code"]);
+    fs::remove_dir_all(&dir).unwrap();
+
+    fn row<'a>(stdout: &'a str, feature: &str) -> Option<Vec<&'a str>> {
+        stdout
+            .lines()
+            .find(|line| line.split_whitespace().next() == Some(feature))
+            .map(|line| line.split_whitespace().skip(1).collect())
+    }
+    assert_eq!(
+        row(&summary, "reference-types"),
+        Some(vec!["1", "25.0%"]),
+        "{summary}"
+    );
+    assert_eq!(row(&summary, "threads"), Some(vec!["1", "25.0%"]));
+    assert_eq!(row(&summary, "memory64"), Some(vec!["0", "0.0%"]));
+    assert_eq!(row(&summary, "sign-ext"), None);
+    assert!(summary.contains("4 modules scanned"), "{summary}");
+    assert_eq!(row(&scanned, "sign-ext"), Some(vec!["1", "25.0%"]));
+}
@@ -0,0 +1,38 @@
+use std::fs;
+use std::path::Path;
+use wasm_lang_inference::{parse_wasm, parse_wasm_with, ParseOptions};
+
+fn fixture(name: &str) -> Vec<u8> {
+    fs::read(
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name),
+    )
+    .unwrap()
+}
+
+#[test]
+fn fingerprints_summarise_the_structure() {
+    let buf = fixture("emscripten.wasm");
+    assert_eq!(
+        parse_wasm(&buf).fingerprint(),
+        "imports[env,wasi_snapshot_preview1] exports[plain] features[] size[<1K]"
+    );
+}
+
+// features are only seen when the code section is scanned
+#[test]
+fn fingerprints_include_the_features_used() {
+    let buf = fixture("emscripten-relaxed-simd.wasm");
+    let module = parse_wasm_with(
+        &buf,
+        &ParseOptions {
+            scan_code: true,
+            ..ParseOptions::default()
+        },
+    );
+    assert_eq!(
+        module.fingerprint(),
+        "imports[env] exports[plain] features[simd,relaxed-simd] size[<1K]"
+    );
+}
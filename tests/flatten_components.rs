@@ -0,0 +1,58 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// each core module in a component, including those in nested components, is reported on its own line
+#[test]
+fn core_modules_in_components_are_classified_separately() {
+    let dir = env::temp_dir().join(format!(
+        "wasm-lang-inference-flatten-{}",
+        std::process::id()
+    ));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    for name in ["component-composed.wasm", "component-plugin.wasm"] {
+        fs::copy(fixtures.join(name), wasm.join(name)).unwrap();
+    }
+
+    let run = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let flattened = run(&["--flatten-components"]);
+    let whole = run(&[]);
+    fs::remove_dir_all(&dir).unwrap();
+
+    let languages = |stdout: &str| -> Vec<String> {
+        stdout
+            .lines()
+            .take_while(|line| !line.is_empty())
+            .map(|line| line.split(", ").take(2).collect::<Vec<_>>().join(" "))
+            .collect()
+    };
+    assert_eq!(
+        languages(&flattened),
+        [
+            "Rust ./wasm/component-composed.wasm#module[0]",
+            "HandWritten ./wasm/component-composed.wasm#module[1]",
+            "Go ./wasm/component-composed.wasm#module[2]",
+            // without any core modules, the component is still reported as a whole
+            "Unknown ./wasm/component-plugin.wasm",
+        ]
+    );
+    assert_eq!(
+        languages(&whole),
+        [
+            "Unknown ./wasm/component-composed.wasm",
+            "Unknown ./wasm/component-plugin.wasm",
+        ]
+    );
+}
@@ -0,0 +1,33 @@
+use std::fs;
+use std::path::Path;
+use wasm_lang_inference::gzip::{gunzip, gunzip_limited};
+
+// compressed with the gzip CLI, so the header carries the original file name
+#[test]
+fn decompresses_a_gzipped_module() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let gzipped = fs::read(fixtures.join("emscripten.wasm.gz")).unwrap();
+    let expected = fs::read(fixtures.join("emscripten.wasm")).unwrap();
+
+    assert_eq!(gunzip(&gzipped), Some(expected));
+}
+
+// a gzip bomb is left compressed rather than filling memory, so the file is skipped as not being wasm
+#[test]
+fn stops_at_the_output_limit() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let gzipped = fs::read(fixtures.join("emscripten.wasm.gz")).unwrap();
+    let len = fs::read(fixtures.join("emscripten.wasm")).unwrap().len();
+
+    assert_eq!(gunzip_limited(&gzipped, len - 1), None);
+    assert_eq!(
+        gunzip_limited(&gzipped, len).map(|out| out.len()),
+        Some(len)
+    );
+}
+
+#[test]
+fn ignores_files_that_arent_gzipped() {
+    assert_eq!(gunzip(b"\0asm\x01\0\0\0"), None);
+    assert_eq!(gunzip(&[0x1f, 0x8b, 0x08, 0x00]), None);
+}
@@ -0,0 +1,46 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// the largest count fills the bar, which is scaled to COLUMNS, and the counts and percentages are kept
+#[test]
+fn summary_is_drawn_as_bars() {
+    let dir = env::temp_dir().join(format!(
+        "wasm-lang-inference-histogram-bars-{}",
+        std::process::id()
+    ));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    for name in [
+        "emscripten.wasm",
+        "emscripten-fs.wasm",
+        "emscripten-idb.wasm",
+        "go-gojs.wasm",
+    ] {
+        fs::copy(fixtures.join(name), wasm.join(name)).unwrap();
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .args(["--histogram-bars", "--count-only", "--color", "always"])
+        .env("COLUMNS", "40")
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    // --count-only prints no per-file lines, and the bars are only coloured when asked for
+    assert!(
+        lines[0].starts_with("\x1b[34mEmscripten\x1b[0m  \x1b[34m█"),
+        "{stdout}"
+    );
+    assert!(lines[0].ends_with("\x1b[0m  3 (75%)"), "{stdout}");
+    assert_eq!(lines[0].matches('█').count(), 18, "{stdout}");
+    assert_eq!(lines[1].matches('█').count(), 6, "{stdout}");
+    assert!(lines[1].ends_with("  1 (25%)"), "{stdout}");
+    assert_eq!(lines[2], "0% unclassified");
+}
@@ -0,0 +1,57 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// imports are counted once per module, most frequent first, and within each language with --by-language
+#[test]
+fn imports_are_tallied_across_modules() {
+    let dir = env::temp_dir().join(format!(
+        "wasm-lang-inference-import-histogram-{}",
+        std::process::id()
+    ));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    for name in [
+        "emscripten-fs.wasm",
+        "emscripten-idb.wasm",
+        "go-gojs.wasm",
+        "java-teavm.wasm",
+    ] {
+        fs::copy(fixtures.join(name), wasm.join(name)).unwrap();
+    }
+
+    let run = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let histogram = run(&["--import-histogram", "2"]);
+    let by_language = run(&["--import-histogram", "2", "--by-language"]);
+    fs::remove_dir_all(&dir).unwrap();
+
+    let rows: Vec<Vec<&str>> = histogram
+        .lines()
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    assert_eq!(rows[0], ["import", "module", "modules", "percent"]);
+    assert_eq!(rows[1], ["env", "2", "50.0%"], "{histogram}");
+    assert_eq!(rows[2].len(), 3);
+    assert!(rows.contains(&vec!["env.emscripten_memcpy_big", "2", "50.0%"]));
+    assert!(histogram.contains("4 modules scanned"), "{histogram}");
+
+    assert!(
+        by_language.contains("emscripten (2 modules)"),
+        "{by_language}"
+    );
+    assert!(by_language.contains("java (1 module)"), "{by_language}");
+    assert!(by_language
+        .lines()
+        .any(|line| line.split_whitespace().collect::<Vec<_>>() == ["teavm", "1", "100.0%"]));
+}
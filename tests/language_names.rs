@@ -0,0 +1,84 @@
+use std::str::FromStr;
+use wasm_lang_inference::json::Json;
+use wasm_lang_inference::Language;
+
+// The names that have been released. Stored results depend on these, so entries may be added but never changed.
+const RELEASED_NAMES: &[&str] = &[
+    "rust",
+    "emscripten",
+    "assemblyscript",
+    "blazor",
+    "unknown",
+    "go",
+    "handwritten",
+    "scheme",
+    "julia",
+    "empty",
+    "object_file",
+    "debug_info",
+    "swift",
+    "dart",
+    "java",
+    "elm",
+    "gren",
+    "coredump",
+];
+
+#[test]
+fn language_names_are_stable() {
+    let names: Vec<&str> = Language::ALL.iter().map(Language::name).collect();
+    assert_eq!(names, RELEASED_NAMES);
+}
+
+#[test]
+fn languages_round_trip_through_display() {
+    for language in Language::ALL {
+        assert_eq!(
+            Language::from_str(&language.to_string()).unwrap(),
+            *language
+        );
+    }
+    assert!(Language::from_str("typescript").is_err());
+}
+
+#[test]
+fn languages_round_trip_through_json() {
+    for language in Language::ALL {
+        let json = Json::from(language.name()).to_string();
+        let parsed: Json = json.parse().unwrap();
+        assert_eq!(
+            parsed.as_str().unwrap().parse::<Language>().unwrap(),
+            *language
+        );
+    }
+}
+
+// names are matched case-insensitively, and misspellings get a suggestion rather than just a refusal
+#[test]
+fn languages_parse_forgivingly() {
+    assert_eq!(Language::from_str("Rust").unwrap(), Language::Rust);
+    assert_eq!(
+        Language::from_str("ObjectFile").unwrap(),
+        Language::ObjectFile
+    );
+
+    let error = Language::from_str("rsut").unwrap_err().to_string();
+    assert!(error.contains("did you mean 'rust'?"), "{error}");
+    let error = Language::from_str("golang").unwrap_err().to_string();
+    assert!(!error.contains("did you mean"), "{error}");
+    assert!(
+        error.contains("rust, emscripten, assemblyscript"),
+        "{error}"
+    );
+}
+
+#[test]
+fn unknown_languages_are_an_error_on_the_command_line() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .args(["explain", "emscriptn"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("did you mean 'emscripten'?"), "{stderr}");
+}
@@ -0,0 +1,61 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use wasm_lang_inference::json::Json;
+
+// shards that share a module merge into one result per module, with the totals on stderr
+#[test]
+fn shards_are_merged_by_hash() {
+    let dir = env::temp_dir().join(format!("wasm-lang-inference-merge-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let shards = [
+        ("a", ["go.wasm", "java-teavm.wasm"]),
+        ("b", ["go.wasm", "elm.wasm"]),
+    ];
+    for (shard, names) in shards {
+        let wasm = dir.join(shard).join("wasm");
+        fs::create_dir_all(&wasm).unwrap();
+        for name in names {
+            fs::copy(fixtures.join(name), wasm.join(name)).unwrap();
+        }
+        let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+            .args(["--format", "json"])
+            .current_dir(dir.join(shard))
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        fs::write(dir.join(format!("{shard}.json")), output.stdout).unwrap();
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .args(["merge", "a.json", "b.json"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    // the older flag spelling is the same command
+    let alias = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .args(["--merge", "a.json", "b.json"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(alias.stdout, output.stdout);
+    assert!(output.status.success());
+    let merged: Json = String::from_utf8(output.stdout).unwrap().parse().unwrap();
+    let languages: Vec<&str> = merged
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| result.get("language").and_then(Json::as_str).unwrap())
+        .collect();
+    assert_eq!(languages.len(), 3);
+    assert!(languages.contains(&"elm") && languages.contains(&"java"));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("4 results across 2 scans, of 3 unique modules"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("{Go: 1, Java: 1, Elm: 1}"), "{stderr}");
+}
@@ -0,0 +1,42 @@
+use std::fs;
+use std::path::Path;
+use wasm_lang_inference::{infer_language_with_mode, parse_wasm, Language, Mode};
+
+// Strict mode only drops rules, so every fixture is either classified the same way in both modes, or left Unknown by
+// strict mode
+#[test]
+fn strict_mode_agrees_with_lenient_or_is_unknown() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures");
+    for entry in fs::read_dir(fixtures).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "wasm") {
+            continue;
+        }
+        let buf = fs::read(&path).unwrap();
+        let module = parse_wasm(&buf);
+        let strict = infer_language_with_mode(&module, Mode::Strict);
+        let lenient = infer_language_with_mode(&module, Mode::Lenient);
+        assert!(
+            strict == lenient || strict == Language::Unknown,
+            "{}: {strict:?} in strict mode, {lenient:?} in lenient mode",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn strict_mode_skips_the_runtime_heuristics() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/julia.wasm");
+    let buf = fs::read(path).unwrap();
+    let module = parse_wasm(&buf);
+    assert_eq!(
+        infer_language_with_mode(&module, Mode::Strict),
+        Language::Unknown
+    );
+    assert_eq!(
+        infer_language_with_mode(&module, Mode::Lenient),
+        Language::Julia
+    );
+}
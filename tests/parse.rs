@@ -0,0 +1,185 @@
+use std::fs;
+use std::path::Path;
+use wasm_lang_inference::{
+    min_feature_level, parse, parse_wasm_with, ExternalKind, ParseError, ParseOptions, TypeRef,
+};
+
+fn fixture(name: &str) -> Vec<u8> {
+    fs::read(
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name),
+    )
+    .unwrap()
+}
+
+// the parsed structure can be inspected with the re-exported wasmparser types
+#[test]
+fn parsed_modules_can_be_inspected() {
+    let buf = fixture("go.wasm");
+    let module = parse(&buf).unwrap();
+    let functions: Vec<&str> = module
+        .imports
+        .iter()
+        .filter(|i| matches!(i.ty, TypeRef::Func(_)))
+        .map(|i| i.name)
+        .collect();
+    assert_eq!(
+        functions,
+        ["debug", "runtime.wasmExit", "syscall/js.valueGet"]
+    );
+    assert!(module
+        .exports
+        .iter()
+        .any(|e| e.name == "mem" && matches!(e.kind, ExternalKind::Memory)));
+}
+
+#[test]
+fn unreadable_modules_are_errors() {
+    assert_eq!(
+        parse(b"\0asm\x02\0\0\0").err(),
+        Some(ParseError::UnsupportedVersion(2))
+    );
+    // the version is only read once the magic number shows it is wasm at all
+    assert_eq!(
+        parse(b"hello world, this is js").err(),
+        Some(ParseError::NotWasm)
+    );
+}
+
+// arithmetic in a data segment's offset is the extended-const proposal, which a plain global.get offset isn't
+#[test]
+fn arithmetic_in_constant_expressions_is_extended_const() {
+    let buf = fixture("emscripten-extended-const.wasm");
+    let module = parse(&buf).unwrap();
+    assert!(module.uses_extended_const);
+    assert!(module.features().contains(&"extended-const"));
+
+    let buf = fixture("emscripten-pic.wasm");
+    assert!(!parse(&buf).unwrap().uses_extended_const);
+}
+
+// a second table, or one holding externrefs, is only possible with reference types
+#[test]
+fn multiple_tables_are_reference_types() {
+    let buf = fixture("emscripten-multi-table.wasm");
+    let module = parse(&buf).unwrap();
+    assert_eq!(module.table_count(), 2);
+    assert!(module.has_externref_table);
+    assert!(module.features().contains(&"reference-types"));
+
+    let buf = fixture("emscripten.wasm");
+    let module = parse(&buf).unwrap();
+    assert!(module.table_count() <= 1 && !module.has_externref_table);
+    assert!(!module.features().contains(&"reference-types"));
+}
+
+// the sign extension and saturating conversion instructions are only seen when the code is scanned
+#[test]
+fn sign_extension_and_saturating_conversions_are_detected() {
+    let buf = fixture("c-modern-features.wasm");
+    let options = ParseOptions {
+        scan_code: true,
+        ..ParseOptions::default()
+    };
+    let module = parse_wasm_with(&buf, &options);
+    assert!(module.uses_sign_ext && module.uses_sat_float_conv);
+    assert_eq!(module.features(), ["sign-ext", "nontrapping-fptoint"]);
+
+    let module = parse(&buf).unwrap();
+    assert!(!module.uses_sign_ext && !module.uses_sat_float_conv);
+}
+
+// the level is the latest release any one feature needs, and threads are outside every release
+#[test]
+fn feature_level_is_the_release_covering_every_feature() {
+    let level = |name: &str| {
+        let buf = fixture(name);
+        let options = ParseOptions {
+            scan_code: true,
+            ..ParseOptions::default()
+        };
+        min_feature_level(&parse_wasm_with(&buf, &options).features())
+    };
+    assert_eq!(level("unknown.wasm"), "1.0");
+    assert_eq!(level("c-modern-features.wasm"), "2.0");
+    assert_eq!(level("rust-panic-unwind.wasm"), "3.0");
+    assert_eq!(level("emscripten-pthread-worker.wasm"), "1.0+threads");
+    assert_eq!(min_feature_level(&["simd", "threads"]), "2.0+threads");
+    assert_eq!(min_feature_level(&["simd", "relaxed-simd"]), "3.0");
+}
+
+fn leb(mut n: usize, out: &mut Vec<u8>) {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+// a module importing `count` functions of type () -> (), `env.f0`, `env.f1`, ...
+fn module_with_imports(count: usize) -> Vec<u8> {
+    let mut imports = vec![];
+    leb(count, &mut imports);
+    for i in 0..count {
+        let name = format!("f{i}");
+        imports.extend([3, b'e', b'n', b'v']);
+        leb(name.len(), &mut imports);
+        imports.extend(name.bytes());
+        imports.extend([0x00, 0x00]);
+    }
+    let mut buf = b"\0asm\x01\0\0\0".to_vec();
+    buf.extend([0x01, 0x04, 0x01, 0x60, 0x00, 0x00]);
+    buf.push(0x02);
+    leb(imports.len(), &mut buf);
+    buf.extend(imports);
+    buf
+}
+
+// an import section far bigger than any real module's is cut short, so the rules only go through so many imports
+#[test]
+fn huge_import_sections_are_cut_short() {
+    let buf = module_with_imports(150_000);
+    let module = parse_wasm_with(&buf, &ParseOptions::default());
+    assert!(module.oversized);
+    assert!(!module.partial);
+    assert_eq!(module.imports.len(), 100_000);
+    assert_eq!(module.imports[99_999].name, "f99999");
+
+    let options = ParseOptions {
+        max_symbols: 10,
+        ..ParseOptions::default()
+    };
+    let module = parse_wasm_with(&buf, &options);
+    assert!(module.oversized && module.imports.len() == 10);
+
+    let buf = module_with_imports(10);
+    assert!(!parse_wasm_with(&buf, &options).oversized);
+}
+
+// the first compilation unit is only read with ParseOptions::deep, from DWARF 5's string offsets or DWARF 4's strp
+#[test]
+fn dwarf_compile_units_are_read_when_deep() {
+    let deep = ParseOptions {
+        deep: true,
+        ..ParseOptions::default()
+    };
+    let buf = fixture("dwarf-rust.wasm");
+    assert!(parse_wasm_with(&buf, &ParseOptions::default())
+        .dwarf
+        .is_none());
+    let unit = parse_wasm_with(&buf, &deep).dwarf.unwrap();
+    assert_eq!(unit.version, 5);
+    assert_eq!(unit.language, Some(0x1c));
+    assert_eq!(unit.producer, Some("clang LLVM (rustc version 1.79.0)"));
+
+    let buf = fixture("dwarf-cpp.wasm");
+    let unit = parse_wasm_with(&buf, &deep).dwarf.unwrap();
+    assert_eq!(unit.version, 4);
+    assert_eq!(unit.language, Some(0x21));
+    assert!(unit.producer.unwrap().starts_with("clang version 17.0.0"));
+}
@@ -0,0 +1,37 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// --paths changes how each file is reported, but not which files are classified or how
+#[test]
+fn paths_are_rendered_in_the_chosen_style() {
+    let dir = env::temp_dir().join(format!("wasm-lang-inference-paths-{}", std::process::id()));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    fs::copy(fixtures.join("julia.wasm"), wasm.join("julia.wasm")).unwrap();
+
+    let run = |style: &str| {
+        let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+            .args(["--paths", style])
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let absolute = run("absolute");
+    let relative = run("relative");
+    let basename = run("basename");
+    let expected = fs::canonicalize(&wasm).unwrap().join("julia.wasm");
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(
+        absolute.starts_with(&format!("Julia, {}\n", expected.display())),
+        "{absolute}"
+    );
+    assert!(relative.starts_with("Julia, julia.wasm\n"), "{relative}");
+    assert!(basename.starts_with("Julia, julia.wasm\n"), "{basename}");
+}
@@ -0,0 +1,65 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+// exactly the listed files are classified, in the order they're listed, and missing ones are skipped
+#[test]
+fn listed_files_are_classified_in_order() {
+    let dir = env::temp_dir().join(format!(
+        "wasm-lang-inference-paths-from-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("crawl/b")).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    fs::copy(fixtures.join("go.wasm"), dir.join("crawl/b/go.wasm")).unwrap();
+    fs::copy(
+        fixtures.join("emscripten.wasm"),
+        dir.join("crawl/emscripten.wasm"),
+    )
+    .unwrap();
+    // not listed, so not classified
+    fs::copy(fixtures.join("julia.wasm"), dir.join("crawl/julia.wasm")).unwrap();
+    let list = "crawl/b/go.wasm\ncrawl/missing.wasm\n\ncrawl/emscripten.wasm\n";
+    fs::write(dir.join("list.txt"), list).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .args(["--paths-from", "list.txt"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .args(["--paths-from", "-"])
+        .current_dir(&dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(list.as_bytes())
+        .unwrap();
+    let piped = child.wait_with_output().unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    for output in [output, piped] {
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let languages: Vec<String> = stdout
+            .lines()
+            .take_while(|line| !line.is_empty())
+            .map(|line| line.split(", ").take(2).collect::<Vec<_>>().join(" "))
+            .collect();
+        assert_eq!(
+            languages,
+            ["Go crawl/b/go.wasm", "Emscripten crawl/emscripten.wasm"]
+        );
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("skipping crawl/missing.wasm"), "{stderr}");
+    }
+}
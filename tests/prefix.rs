@@ -0,0 +1,77 @@
+use std::fs;
+use std::path::Path;
+use wasm_lang_inference::{infer_language, infer_language_prefix, parse_wasm, Language};
+
+// Every prefix of every fixture either asks for more bytes or agrees with the classification of the whole module
+#[test]
+fn prefix_classification_agrees_with_whole_module() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures");
+    for entry in fs::read_dir(fixtures).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "wasm") {
+            continue;
+        }
+        let buf = fs::read(&path).unwrap();
+        let expected = infer_language(&parse_wasm(&buf));
+        for len in 0..=buf.len() {
+            if let Some(language) = infer_language_prefix(&buf[..len]) {
+                assert_eq!(language, expected, "{} at {len} bytes", path.display());
+            }
+        }
+    }
+}
+
+// JWebAssembly's class names are in the name section, after the code, so without its imports no prefix is enough
+#[test]
+fn prefix_of_a_module_known_by_its_names_needs_more_bytes() {
+    let buf = fs::read(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/java-jwebassembly-gc.wasm"),
+    )
+    .unwrap();
+    assert_eq!(infer_language(&parse_wasm(&buf)), Language::Java);
+    for len in 0..=buf.len() {
+        assert_eq!(infer_language_prefix(&buf[..len]), None, "at {len} bytes");
+    }
+}
+
+#[test]
+fn prefix_that_stops_in_the_imports_needs_more_bytes() {
+    let buf = fs::read(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/rust-wasm-bindgen.wasm"),
+    )
+    .unwrap();
+    assert_eq!(infer_language_prefix(&buf[..20]), None);
+    assert!(infer_language_prefix(&buf).is_some());
+}
+
+// A prefix that ends between two sections, before the export section, isn't the whole module - here the exports are
+// what make it Rust rather than AssemblyScript
+#[test]
+fn prefix_that_stops_between_sections_needs_more_bytes() {
+    let mut buf = b"\0asm\x01\0\0\0".to_vec();
+    // (type (func (param i32 i32 i32 i32)))
+    buf.extend([0x01, 0x08, 0x01, 0x60, 0x04, 0x7f, 0x7f, 0x7f, 0x7f, 0x00]);
+    // (import "env" "abort" (func (type 0)))
+    buf.extend([0x02, 0x0d, 0x01, 0x03]);
+    buf.extend(b"env");
+    buf.push(0x05);
+    buf.extend(b"abort");
+    buf.extend([0x00, 0x00]);
+    // (func (type 0))
+    buf.extend([0x03, 0x02, 0x01, 0x00]);
+    let exports_start = buf.len();
+    // (export "__wbindgen_malloc" (func 1))
+    buf.extend([0x07, 0x15, 0x01, 0x11]);
+    buf.extend(b"__wbindgen_malloc");
+    buf.extend([0x00, 0x01]);
+    // (func unreachable)
+    buf.extend([0x0a, 0x05, 0x01, 0x03, 0x00, 0x00, 0x0b]);
+
+    assert_eq!(infer_language(&parse_wasm(&buf)), Language::Rust);
+    for len in [exports_start - 4, exports_start] {
+        assert_eq!(infer_language_prefix(&buf[..len]), None, "at {len} bytes");
+    }
+    assert_eq!(infer_language_prefix(&buf), Some(Language::Rust));
+}
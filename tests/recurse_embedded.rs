@@ -0,0 +1,80 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use wasm_lang_inference::custom_section_modules;
+
+// a module embedded in a custom section, and one embedded in that, are reported alongside the module that holds them
+#[test]
+fn modules_in_custom_sections_are_classified_too() {
+    let dir = env::temp_dir().join(format!(
+        "wasm-lang-inference-recurse-embedded-{}",
+        std::process::id()
+    ));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    for name in ["embedded-in-custom.wasm", "go.wasm"] {
+        fs::copy(fixtures.join(name), wasm.join(name)).unwrap();
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .arg("--recurse-embedded")
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let languages: Vec<String> = stdout
+        .lines()
+        .take_while(|line| !line.is_empty())
+        .map(|line| line.split(", ").take(2).collect::<Vec<_>>().join(" "))
+        .collect();
+    assert_eq!(
+        languages,
+        [
+            "HandWritten ./wasm/embedded-in-custom.wasm",
+            "Emscripten ./wasm/embedded-in-custom.wasm#custom[fallback]",
+            "Unknown ./wasm/embedded-in-custom.wasm#custom[fallback]#custom[inner]",
+            "Go ./wasm/go.wasm",
+        ]
+    );
+}
+
+fn leb(mut n: usize) -> Vec<u8> {
+    let mut bytes = vec![];
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+// a module holding nothing but a custom section named `nested`, which holds `inner`
+fn wrap(inner: &[u8]) -> Vec<u8> {
+    let mut payload = leb(6);
+    payload.extend_from_slice(b"nested");
+    payload.extend_from_slice(inner);
+    let mut module = b"\0asm\x01\0\0\0\0".to_vec();
+    module.extend(leb(payload.len()));
+    module.extend(payload);
+    module
+}
+
+// however deeply a crafted module nests them, the search stops at a fixed depth
+#[test]
+fn nesting_is_searched_to_a_limited_depth() {
+    let mut module = b"\0asm\x01\0\0\0".to_vec();
+    for _ in 0..100 {
+        module = wrap(&module);
+    }
+    let modules = custom_section_modules(&module);
+    assert_eq!(modules.len(), 8);
+    assert_eq!(modules[1].0, "#custom[nested]#custom[nested]");
+}
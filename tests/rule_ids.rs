@@ -0,0 +1,133 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use wasm_lang_inference::json::Json;
+use wasm_lang_inference::RULES;
+
+// Every rule id that has been released. Like the language names, people aggregate results by these, so an id must
+// never be changed or reused once released - new rules add new ids.
+const RELEASED_IDS: &[&str] = &[
+    "emscripten.import",
+    "blazor.import",
+    "rust.wasm_bindgen",
+    "go.import_module",
+    "swift.javascriptkit",
+    "assemblyscript.hyphenate",
+    "assemblyscript.abort",
+    "assemblyscript.arguments_glue",
+    "dart.dart2wasm",
+    "java.teavm",
+    "java.jwebassembly",
+    "scheme.runtime",
+    "julia.runtime",
+    "emscripten.embind",
+    "emscripten.stack",
+    "emscripten.invoke",
+    "emscripten.likely",
+    "emscripten.side_module",
+    "emscripten.static_constructors",
+    "rust.dwarf",
+    "swift.dwarf",
+    "clang.dwarf",
+    "emscripten.qt",
+    "rust.toolchain",
+    "swift.runtime",
+    "go.wasip1",
+    "java.jwebassembly_names",
+    "clang.wasi_main",
+    "rust.source_map",
+    "emscripten.source_map",
+    "go.source_map",
+    "assemblyscript.source_map",
+    "elm.definitions",
+    "gren.definitions",
+    "handwritten.profile",
+    "handwritten.no_toolchain",
+];
+
+#[test]
+fn rule_ids_are_stable() {
+    let ids: Vec<&str> = RULES.iter().map(|rule| rule.id).collect();
+    for id in RELEASED_IDS {
+        assert!(ids.contains(id), "released rule id '{id}' is gone");
+    }
+    for (i, id) in ids.iter().enumerate() {
+        assert!(!ids[..i].contains(id), "'{id}' is used by two rules");
+        // `<language or toolchain>.<signal>`, in lower snake case
+        let parts: Vec<&str> = id.split('.').collect();
+        assert_eq!(parts.len(), 2, "{id}");
+        assert!(
+            parts.iter().all(|part| !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')),
+            "{id}"
+        );
+    }
+}
+
+// the JSON results name the deciding rule, and with --evidence every rule that matched - which includes another
+// language's rules for polyglot modules, flagged as such in their hints
+#[test]
+fn results_report_rule_ids() {
+    let dir = env::temp_dir().join(format!(
+        "wasm-lang-inference-rule-ids-{}",
+        std::process::id()
+    ));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    for name in ["emscripten-fs.wasm", "rust-emscripten.wasm", "unknown.wasm"] {
+        fs::copy(fixtures.join(name), wasm.join(name)).unwrap();
+    }
+    let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .args(["--format", "json", "--evidence"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(output.status.success());
+    let json: Json = String::from_utf8(output.stdout).unwrap().parse().unwrap();
+    let result = |name: &str| {
+        json.as_array()
+            .unwrap()
+            .iter()
+            .find(|r| {
+                r.get("path")
+                    .and_then(Json::as_str)
+                    .unwrap()
+                    .ends_with(name)
+            })
+            .unwrap()
+            .clone()
+    };
+
+    let emscripten = result("emscripten-fs.wasm");
+    assert_eq!(
+        emscripten.get("rule").and_then(Json::as_str),
+        Some("emscripten.import")
+    );
+    let evidence: Vec<&str> = emscripten
+        .get("evidence")
+        .and_then(Json::as_array)
+        .unwrap()
+        .iter()
+        .map(|id| id.as_str().unwrap())
+        .collect();
+    assert_eq!(evidence.first(), Some(&"emscripten.import"));
+    assert_eq!(emscripten.get("hints").unwrap().get("polyglot"), None);
+    assert_eq!(
+        result("rust-emscripten.wasm")
+            .get("hints")
+            .unwrap()
+            .get("polyglot")
+            .and_then(Json::as_str),
+        Some("emscripten,rust")
+    );
+
+    let unknown = result("unknown.wasm");
+    assert_eq!(unknown.get("rule"), Some(&Json::Null));
+    assert_eq!(unknown.get("evidence"), Some(&Json::Array(vec![])));
+}
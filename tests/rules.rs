@@ -0,0 +1,307 @@
+use std::fs;
+use std::path::Path;
+use wasm_lang_inference::{
+    classify, infer_all_languages, parse_wasm, parse_wasm_with, Language, ParseOptions, Tier,
+};
+
+fn fixture(name: &str) -> Vec<u8> {
+    fs::read(
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name),
+    )
+    .unwrap()
+}
+
+// The stack functions that Emscripten's glue calls by name are a much surer sign than the minified import names
+#[test]
+fn minified_module_with_stack_save_is_high_confidence_emscripten() {
+    let buf = fixture("emscripten-minified-stack.wasm");
+    let classification = classify(&parse_wasm(&buf));
+    assert_eq!(classification.language, Language::Emscripten);
+    assert_eq!(classification.rule.unwrap().id, "emscripten.stack");
+    assert!(classification.confidence >= 0.9);
+
+    let buf = fixture("emscripten-minified.wasm");
+    let fallback = classify(&parse_wasm(&buf));
+    assert_eq!(fallback.rule.unwrap().id, "emscripten.likely");
+    assert!(fallback.confidence < classification.confidence);
+}
+
+// Only the weak import / export patterns are trusted less on stripped modules
+#[test]
+fn stripped_modules_lower_the_confidence_of_weak_patterns() {
+    let buf = fixture("emscripten-minified.wasm");
+    let module = parse_wasm(&buf);
+    assert!(module.is_stripped());
+    let classification = classify(&module);
+    assert!(classification.confidence < classification.rule.unwrap().confidence);
+
+    let buf = fixture("emscripten-minified-stack.wasm");
+    let classification = classify(&parse_wasm(&buf));
+    assert_eq!(
+        classification.confidence,
+        classification.rule.unwrap().confidence
+    );
+
+    let buf = fixture("debug-names.wasm");
+    let module = parse_wasm(&buf);
+    assert!(!module.is_stripped());
+}
+
+// a Rust crate built for Emscripten has strong signatures of both, rather than just the one that is picked
+#[test]
+fn modules_linked_from_several_languages_have_them_all() {
+    let buf = fixture("rust-emscripten.wasm");
+    let module = parse_wasm(&buf);
+    assert_eq!(
+        infer_all_languages(&module),
+        [Language::Emscripten, Language::Rust]
+    );
+    assert_eq!(classify(&module).hints["polyglot"], "emscripten,rust");
+
+    let buf = fixture("rust-wasi.wasm");
+    let module = parse_wasm(&buf);
+    assert_eq!(infer_all_languages(&module), [Language::Rust]);
+    assert!(!classify(&module).hints.contains_key("polyglot"));
+}
+
+// wasi-libc's main shims are only ever defined by clang, so they are evidence enough without any producers section
+#[test]
+fn wasi_libc_main_shims_are_clang() {
+    let buf = fixture("c-wasi-main.wasm");
+    let classification = classify(&parse_wasm(&buf));
+    assert_eq!(classification.language, Language::Emscripten);
+    assert_eq!(classification.rule.unwrap().id, "clang.wasi_main");
+    assert_eq!(classification.hints["wasi_exec_model"], "command");
+}
+
+// SwiftWasm targets WASI either way, and JavaScriptKit is what marks out the modules built for the browser
+#[test]
+fn swift_modules_are_told_apart_by_their_environment() {
+    let buf = fixture("swift-javascriptkit.wasm");
+    let classification = classify(&parse_wasm(&buf));
+    assert_eq!(classification.language, Language::Swift);
+    assert_eq!(classification.rule.unwrap().id, "swift.javascriptkit");
+    assert_eq!(classification.hints["environment"], "browser");
+
+    let buf = fixture("swift-wasi.wasm");
+    let module = parse_wasm(&buf);
+    let classification = classify(&module);
+    assert_eq!(classification.language, Language::Swift);
+    assert_eq!(classification.rule.unwrap().id, "swift.runtime");
+    assert_eq!(classification.hints["environment"], "wasi");
+    assert_eq!(infer_all_languages(&module), [Language::Swift]);
+}
+
+// dart2wasm's GC modules are Dart, and Flutter's are told apart by their renderer
+#[test]
+fn dart2wasm_modules_are_dart() {
+    let buf = fixture("dart-dart2wasm.wasm");
+    let classification = classify(&parse_wasm(&buf));
+    assert_eq!(classification.language, Language::Dart);
+    assert_eq!(classification.rule.unwrap().id, "dart.dart2wasm");
+    assert!(!classification.hints.contains_key("framework"));
+
+    let buf = fixture("dart-flutter.wasm");
+    let classification = classify(&parse_wasm(&buf));
+    assert_eq!(classification.language, Language::Dart);
+    assert_eq!(classification.hints["framework"], "flutter");
+}
+
+// a cluster of the file system's syscalls is file I/O, but stdio on its own isn't
+#[test]
+fn emscripten_file_system_use_is_a_hint() {
+    let hint = |name| {
+        let buf = fixture(name);
+        classify(&parse_wasm(&buf))
+            .hints
+            .get("uses_emscripten_fs")
+            .cloned()
+    };
+    assert_eq!(hint("emscripten-fs.wasm").as_deref(), Some("memfs"));
+    assert_eq!(hint("emscripten-idb.wasm").as_deref(), Some("idb"));
+    assert_eq!(hint("emscripten.wasm"), None);
+}
+
+// TeaVM and JWebAssembly are both Java, told apart by the toolchain hint
+#[test]
+fn java_backends_are_told_apart() {
+    let buf = fixture("java-teavm.wasm");
+    let classification = classify(&parse_wasm(&buf));
+    assert_eq!(classification.language, Language::Java);
+    assert_eq!(classification.rule.unwrap().id, "java.teavm");
+    assert_eq!(classification.hints["toolchain"], "teavm");
+
+    let buf = fixture("java-jwebassembly.wasm");
+    let classification = classify(&parse_wasm(&buf));
+    assert_eq!(classification.language, Language::Java);
+    assert_eq!(classification.hints["toolchain"], "jwebassembly");
+}
+
+// Elm and Gren are only recognised by their compilers' naming of definitions, after every other rule
+#[test]
+fn elm_and_gren_are_recognised_by_definition_names() {
+    let buf = fixture("elm.wasm");
+    let classification = classify(&parse_wasm(&buf));
+    assert_eq!(classification.language, Language::Elm);
+    assert_eq!(classification.rule.unwrap().tier, Tier::Weak);
+
+    let buf = fixture("gren.wasm");
+    assert_eq!(classify(&parse_wasm(&buf)).language, Language::Gren);
+}
+
+// the optional arguments glue is AssemblyScript's alone, so is enough without the env.abort import
+#[test]
+fn assemblyscript_bindings_glue_is_recognised() {
+    let buf = fixture("assemblyscript-bindings.wasm");
+    let classification = classify(&parse_wasm(&buf));
+    assert_eq!(classification.language, Language::AssemblyScript);
+    assert_eq!(
+        classification.rule.unwrap().id,
+        "assemblyscript.arguments_glue"
+    );
+    assert_eq!(classification.hints["interop"], "bindings");
+
+    let buf = fixture("assemblyscript-abort.wasm");
+    assert!(!classify(&parse_wasm(&buf)).hints.contains_key("interop"));
+}
+
+// the DWARF source language outranks every other rule, but is only read with ParseOptions::deep
+#[test]
+fn dwarf_source_language_comes_first() {
+    let deep = ParseOptions {
+        deep: true,
+        ..ParseOptions::default()
+    };
+    let buf = fixture("dwarf-rust.wasm");
+    assert_ne!(classify(&parse_wasm(&buf)).language, Language::Rust);
+    let classification = classify(&parse_wasm_with(&buf, &deep));
+    assert_eq!(classification.language, Language::Rust);
+    assert_eq!(classification.rule.unwrap().id, "rust.dwarf");
+    assert_eq!(classification.hints["debug_format"], "dwarf5");
+
+    let buf = fixture("dwarf-cpp.wasm");
+    let classification = classify(&parse_wasm_with(&buf, &deep));
+    assert_eq!(classification.language, Language::Emscripten);
+    assert_eq!(classification.rule.unwrap().id, "clang.dwarf");
+    assert_eq!(classification.hints["source_language"], "cpp");
+}
+
+// calls that might throw go out through the JS glue's invoke_* thunks, unless the build uses wasm exceptions
+#[test]
+fn emscripten_exception_handling_is_told_apart() {
+    let buf = fixture("emscripten-invoke.wasm");
+    let classification = classify(&parse_wasm(&buf));
+    assert_eq!(classification.language, Language::Emscripten);
+    assert_eq!(classification.rule.unwrap().id, "emscripten.invoke");
+    assert_eq!(classification.hints["exception_handling"], "js");
+
+    let buf = fixture("emscripten-wasm-exceptions.wasm");
+    let classification = classify(&parse_wasm(&buf));
+    assert_eq!(classification.language, Language::Emscripten);
+    assert_eq!(classification.hints["exception_handling"], "wasm");
+
+    let buf = fixture("emscripten.wasm");
+    assert!(!classify(&parse_wasm(&buf))
+        .hints
+        .contains_key("exception_handling"));
+}
+
+// only what a module imports functions from matters: storage it imports any runtime can supply
+#[test]
+fn runnability_follows_the_imported_functions() {
+    let runnability = |name: &str| {
+        let buf = fixture(name);
+        classify(&parse_wasm(&buf)).runnability
+    };
+    assert_eq!(runnability("handwritten-math.wasm"), Some("standalone"));
+    assert_eq!(runnability("wasi-reactor.wasm"), Some("wasi"));
+    assert_eq!(runnability("rust-wasm-bindgen.wasm"), Some("needs-js-glue"));
+    // a side module imports only its base addresses, but can only be loaded by Emscripten's dynamic linker
+    assert_eq!(
+        runnability("emscripten-side-module.wasm"),
+        Some("needs-js-glue")
+    );
+    assert_eq!(runnability("debug-info-companion.wasm"), None);
+}
+
+// frameworks are told from their crates' panic paths, and the ecosystem crates from what they import through wbg
+#[test]
+fn rust_frameworks_and_crates_are_hints() {
+    let hints = |name: &str| {
+        let buf = fixture(name);
+        classify(&parse_wasm(&buf)).hints
+    };
+    let yew = hints("rust-yew.wasm");
+    assert_eq!(yew["framework"], "yew");
+    assert_eq!(yew["crates"], "getrandom,js-sys,web-sys,gloo");
+    assert_eq!(hints("rust-leptos.wasm")["framework"], "leptos");
+    assert_eq!(hints("rust-bevy-wgpu.wasm")["framework"], "bevy");
+
+    let plain = hints("rust-wasm-bindgen.wasm");
+    assert!(!plain.contains_key("framework"));
+    assert!(!plain.contains_key("crates"));
+}
+
+// ICU is seen in the runtime's data and names, and its absence only counts in the runtime itself
+#[test]
+fn blazor_globalization_is_a_hint() {
+    let globalization = |name: &str| {
+        let buf = fixture(name);
+        classify(&parse_wasm(&buf))
+            .hints
+            .get("globalization")
+            .cloned()
+    };
+    assert_eq!(globalization("blazor-icu.wasm").as_deref(), Some("icu"));
+    assert_eq!(
+        globalization("blazor-csharp.wasm").as_deref(),
+        Some("invariant")
+    );
+}
+
+// C modules are hosted when they have libc's startup code, and freestanding when they have no libc to speak of
+#[test]
+fn c_startup_code_is_told_apart() {
+    let startup = |name: &str| {
+        let buf = fixture(name);
+        classify(&parse_wasm(&buf)).hints.get("startup").cloned()
+    };
+    assert_eq!(startup("c-wasi-main.wasm").as_deref(), Some("hosted"));
+    assert_eq!(
+        startup("c-static-ctors.wasm").as_deref(),
+        Some("freestanding")
+    );
+    // Emscripten's JS glue starts the module up, which leaves nothing to go on
+    assert_eq!(startup("emscripten-fs.wasm"), None);
+}
+
+// a coredump holds a crashed program's memory, which is full of the program's strings, but isn't a program itself
+#[test]
+fn coredumps_are_kept_apart() {
+    let buf = fixture("coredump.wasm");
+    let classification = classify(&parse_wasm(&buf));
+    assert_eq!(classification.language, Language::Coredump);
+    assert!(classification.rule.is_none());
+}
+
+// Go's two wasm targets, told apart by whether the JS bridge or only WASI is imported
+#[test]
+fn go_targets_are_told_apart() {
+    let target = |name: &str| {
+        let buf = fixture(name);
+        let classification = classify(&parse_wasm(&buf));
+        assert_eq!(classification.language, Language::Go, "{name}");
+        classification.hints["target"].clone()
+    };
+    assert_eq!(target("go-gojs.wasm"), "js/wasm");
+    assert_eq!(target("go-tinygo.wasm"), "js/wasm");
+    assert_eq!(target("go-wasip1.wasm"), "wasip1/wasm");
+    assert_eq!(target("go-tinygo-wasip1.wasm"), "wasip1/wasm");
+
+    let buf = fixture("go-wasip1.wasm");
+    let classification = classify(&parse_wasm(&buf));
+    assert_eq!(classification.rule.unwrap().id, "go.wasip1");
+    assert_eq!(classification.hints["go_era"], "1.21+");
+}
@@ -0,0 +1,91 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use wasm_lang_inference::json::Json;
+
+// The checks the SARIF 2.1.0 schema makes of the parts of the log that --format sarif writes: the required properties,
+// their types and enumerations, and that each result's ruleIndex refers to the rule it names.
+fn validate(log: &Json) {
+    assert_eq!(log.get("version").and_then(Json::as_str), Some("2.1.0"));
+    assert!(log.get("$schema").and_then(Json::as_str).is_some());
+    let runs = log.get("runs").and_then(Json::as_array).unwrap();
+    assert!(!runs.is_empty());
+    for run in runs {
+        let driver = run.get("tool").and_then(|t| t.get("driver")).unwrap();
+        assert!(driver.get("name").and_then(Json::as_str).is_some());
+        let rules = driver.get("rules").and_then(Json::as_array).unwrap();
+        for rule in rules {
+            assert!(rule.get("id").and_then(Json::as_str).is_some());
+            let description = rule.get("shortDescription").unwrap();
+            assert!(description.get("text").and_then(Json::as_str).is_some());
+        }
+
+        for result in run.get("results").and_then(Json::as_array).unwrap() {
+            let message = result.get("message").unwrap();
+            assert!(message.get("text").and_then(Json::as_str).is_some());
+            let rule_id = result.get("ruleId").and_then(Json::as_str).unwrap();
+            let rule_index = match result.get("ruleIndex") {
+                Some(Json::Number(n)) if *n >= 0.0 && n.fract() == 0.0 => *n as usize,
+                index => panic!("invalid ruleIndex {index:?}"),
+            };
+            assert_eq!(
+                rules[rule_index].get("id").and_then(Json::as_str),
+                Some(rule_id)
+            );
+            let kind = result.get("kind").and_then(Json::as_str).unwrap();
+            assert!([
+                "notApplicable",
+                "pass",
+                "fail",
+                "review",
+                "open",
+                "informational"
+            ]
+            .contains(&kind));
+            let level = result.get("level").and_then(Json::as_str).unwrap();
+            assert!(["none", "note", "warning", "error"].contains(&level));
+            // a result that isn't a failure must not have a level other than none
+            assert!(kind == "fail" || level == "none");
+
+            let locations = result.get("locations").and_then(Json::as_array).unwrap();
+            for location in locations {
+                let uri = location
+                    .get("physicalLocation")
+                    .and_then(|l| l.get("artifactLocation"))
+                    .and_then(|l| l.get("uri"))
+                    .and_then(Json::as_str)
+                    .unwrap();
+                assert!(!uri.contains(' ') && !uri.contains('\\'), "{uri}");
+            }
+        }
+    }
+}
+
+#[test]
+fn sarif_output_is_valid() {
+    let dir = env::temp_dir().join(format!("wasm-lang-inference-sarif-{}", std::process::id()));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    for name in ["go.wasm", "rust-wasm-bindgen.wasm", "unknown.wasm"] {
+        fs::copy(fixtures.join(name), wasm.join(name)).unwrap();
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .args(["--format", "sarif"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success());
+    let log: Json = String::from_utf8(output.stdout).unwrap().parse().unwrap();
+    validate(&log);
+    let results = log.get("runs").and_then(Json::as_array).unwrap()[0]
+        .get("results")
+        .and_then(Json::as_array)
+        .unwrap();
+    assert_eq!(results.len(), 3);
+}
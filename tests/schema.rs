@@ -0,0 +1,141 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use wasm_lang_inference::json::Json;
+
+fn run(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn has_type(value: &Json, ty: &str) -> bool {
+    match (ty, value) {
+        ("null", Json::Null) | ("boolean", Json::Bool(_)) | ("number", Json::Number(_)) => true,
+        ("integer", Json::Number(n)) => n.fract() == 0.0,
+        ("string", Json::String(_)) | ("array", Json::Array(_)) | ("object", Json::Object(_)) => {
+            true
+        }
+        _ => false,
+    }
+}
+
+fn check(condition: bool, error: impl FnOnce() -> String) -> Result<(), String> {
+    if condition {
+        Ok(())
+    } else {
+        Err(error())
+    }
+}
+
+// The subset of JSON Schema that --json-schema uses: type (or types), enum, items, properties, required and
+// additionalProperties, with oneOf and $refs into $defs
+fn validate(root: &Json, schema: &Json, value: &Json, at: &str) -> Result<(), String> {
+    if let Some(reference) = schema.get("$ref").and_then(Json::as_str) {
+        let name = reference.strip_prefix("#/$defs/").unwrap();
+        return validate(
+            root,
+            root.get("$defs").unwrap().get(name).unwrap(),
+            value,
+            at,
+        );
+    }
+    if let Some(options) = schema.get("oneOf").and_then(Json::as_array) {
+        let matching = options
+            .iter()
+            .filter(|option| validate(root, option, value, at).is_ok())
+            .count();
+        return check(matching == 1, || {
+            format!("{at} matches {matching} of oneOf")
+        });
+    }
+    match schema.get("type") {
+        Some(Json::String(ty)) => check(has_type(value, ty), || {
+            format!("{at} isn't {ty}: {value:?}")
+        })?,
+        Some(Json::Array(types)) => check(
+            types.iter().any(|ty| has_type(value, ty.as_str().unwrap())),
+            || format!("{at} isn't any of {types:?}: {value:?}"),
+        )?,
+        _ => {}
+    }
+    if let Some(values) = schema.get("enum").and_then(Json::as_array) {
+        check(values.contains(value), || {
+            format!("{at} isn't one of {values:?}: {value:?}")
+        })?;
+    }
+    if let (Some(items), Json::Array(values)) = (schema.get("items"), value) {
+        for (index, item) in values.iter().enumerate() {
+            validate(root, items, item, &format!("{at}[{index}]"))?;
+        }
+    }
+    if let Json::Object(entries) = value {
+        let properties = schema.get("properties");
+        for required in schema
+            .get("required")
+            .and_then(Json::as_array)
+            .unwrap_or(&[])
+        {
+            let required = required.as_str().unwrap();
+            check(value.get(required).is_some(), || {
+                format!("{at} is missing {required}")
+            })?;
+        }
+        for (key, property) in entries {
+            let at = format!("{at}.{key}");
+            match (
+                properties.and_then(|p| p.get(key)),
+                schema.get("additionalProperties"),
+            ) {
+                (Some(property_schema), _) => validate(root, property_schema, property, &at)?,
+                (None, Some(Json::Bool(false))) => return Err(format!("{at} isn't in the schema")),
+                (None, Some(additional)) => validate(root, additional, property, &at)?,
+                (None, None) => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn json_output_matches_the_schema() {
+    let dir = env::temp_dir().join(format!("wasm-lang-inference-schema-{}", std::process::id()));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    for name in [
+        "go.wasm",
+        "rust-wasm-bindgen.wasm",
+        "rust-truncated.wasm",
+        "unknown.wasm",
+    ] {
+        fs::copy(fixtures.join(name), wasm.join(name)).unwrap();
+    }
+
+    let schema: Json = run(&dir, &["--json-schema"]).parse().unwrap();
+    let json: Json = run(&dir, &["--format", "json"]).parse().unwrap();
+    validate(&schema, &schema, &json, "$").unwrap();
+    // with the optional parts of the results filled in
+    for line in run(
+        &dir,
+        &[
+            "--format",
+            "ndjson",
+            "--stats",
+            "--dump-symbols",
+            "--fingerprint",
+            "--evidence",
+        ],
+    )
+    .lines()
+    {
+        validate(&schema, &schema, &line.parse().unwrap(), "$").unwrap();
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,92 @@
+#![cfg(feature = "server")]
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+fn request(address: &str, head: &str, body: &[u8]) -> String {
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream.write_all(head.as_bytes()).unwrap();
+    stream.write_all(body).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+fn post(address: &str, body: &[u8]) -> String {
+    let head = format!(
+        "POST /classify HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n",
+        body.len()
+    );
+    request(address, &head, body)
+}
+
+#[test]
+fn serve_classifies_posted_modules() {
+    let mut server = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .args([
+            "serve",
+            "--port",
+            "0",
+            "--max-body",
+            "4096",
+            "--workers",
+            "2",
+        ])
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    BufReader::new(server.stderr.as_mut().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    let address = line
+        .trim()
+        .strip_prefix("listening on http://")
+        .unwrap()
+        .to_string();
+
+    let health = request(&address, "GET /health HTTP/1.1\r\n\r\n", b"");
+    let go =
+        fs::read(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/go.wasm")).unwrap();
+    let classified = post(&address, &go);
+    let not_wasm = post(&address, b"hello");
+    let unsupported = post(&address, b"\0asm\x02\0\0\0");
+    // only the head, as the server doesn't read a body that is over the limit
+    let too_large = request(
+        &address,
+        "POST /classify HTTP/1.1\r\nContent-Length: 8192\r\n\r\n",
+        b"",
+    );
+    let truncated = {
+        let mut stream = TcpStream::connect(&address).unwrap();
+        stream
+            .write_all(b"POST /classify HTTP/1.1\r\nContent-Length: 100\r\n\r\n\0asm")
+            .unwrap();
+        stream.shutdown(Shutdown::Write).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    };
+    let not_found = request(&address, "GET / HTTP/1.1\r\n\r\n", b"");
+    let malformed = request(&address, "nonsense\r\n\r\n", b"");
+    server.kill().unwrap();
+    server.wait().unwrap();
+
+    assert!(health.starts_with("HTTP/1.1 200 OK"), "{health}");
+    assert!(classified.starts_with("HTTP/1.1 200 OK"), "{classified}");
+    assert!(classified.contains(r#""language":"go""#), "{classified}");
+    assert!(not_wasm.starts_with("HTTP/1.1 400"), "{not_wasm}");
+    assert!(unsupported.starts_with("HTTP/1.1 400"), "{unsupported}");
+    assert!(
+        unsupported.contains("unsupported wasm version"),
+        "{unsupported}"
+    );
+    assert!(too_large.starts_with("HTTP/1.1 413"), "{too_large}");
+    assert!(truncated.starts_with("HTTP/1.1 400"), "{truncated}");
+    assert!(truncated.contains("shorter than"), "{truncated}");
+    assert!(not_found.starts_with("HTTP/1.1 404"), "{not_found}");
+    assert!(malformed.starts_with("HTTP/1.1 400"), "{malformed}");
+}
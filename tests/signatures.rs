@@ -0,0 +1,110 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use wasm_lang_inference::signatures::Signatures;
+use wasm_lang_inference::{classify, parse_wasm, Language};
+
+fn fixture(name: &str) -> Vec<u8> {
+    fs::read(
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name),
+    )
+    .unwrap()
+}
+
+fn starter() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("signatures/long-tail.json")
+}
+
+// the starter file is loaded as-is, and its exports-only JavaScriptKit signature catches what the built-in rules miss
+#[test]
+fn starter_signatures_match_modules_the_rules_dont() {
+    let mut signatures = Signatures::default();
+    signatures.load(&starter()).unwrap();
+    assert!(!signatures.signatures().is_empty());
+
+    let buf = fixture("swift-exports-only.wasm");
+    let module = parse_wasm(&buf);
+    assert_eq!(classify(&module).language, Language::Unknown);
+    let signature = signatures.matching_signature(&module).unwrap();
+    assert_eq!(signature.id, "swift.javascriptkit_exports");
+    assert_eq!(signature.language, Language::Swift);
+
+    let buf = fixture("unknown.wasm");
+    assert!(signatures.matching_signature(&parse_wasm(&buf)).is_none());
+}
+
+fn load_error(json: &str) -> String {
+    let path = env::temp_dir().join(format!(
+        "wasm-lang-inference-signatures-{}-{}.json",
+        std::process::id(),
+        json.len()
+    ));
+    fs::write(&path, json).unwrap();
+    let error = Signatures::default().load(&path).unwrap_err();
+    fs::remove_file(&path).unwrap();
+    format!("{error:#}")
+}
+
+#[test]
+fn invalid_signatures_are_reported() {
+    let error = load_error(
+        r#"{"signatures": [{"id": "a", "language": "swfit", "confidence": 0.5, "export_name": "x"}]}"#,
+    );
+    assert!(error.contains("invalid signature at index 0"), "{error}");
+    assert!(error.contains("did you mean 'swift'?"), "{error}");
+
+    let error =
+        load_error(r#"{"signatures": [{"id": "a", "language": "swift", "confidence": 0.5}]}"#);
+    assert!(error.contains("needs at least one of"), "{error}");
+
+    let error = load_error(
+        r#"{"signatures": [{"id": "a", "language": "swift", "confidence": 5, "export_name": "x"}]}"#,
+    );
+    assert!(
+        error.contains("'confidence' must be a number from 0 to 1"),
+        "{error}"
+    );
+
+    let error = load_error(
+        r#"{"signatures": [{"id": "a", "language": "swift", "confidence": 0.5, "exports": "x"}]}"#,
+    );
+    assert!(error.contains("unknown key 'exports'"), "{error}");
+
+    let error = load_error(
+        r#"{"signatures": [{"id": "a", "language": "swift", "confidence": 0.5, "export_name": "x"}, {"id": "a", "language": "go", "confidence": 0.5, "export_name": "y"}]}"#,
+    );
+    assert!(
+        error.contains("already a signature with the id 'a'"),
+        "{error}"
+    );
+}
+
+// signatures only get a say on modules the built-in rules leave Unknown
+#[test]
+fn scan_applies_signatures_after_the_rules() {
+    let dir = env::temp_dir().join(format!(
+        "wasm-lang-inference-signatures-{}",
+        std::process::id()
+    ));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    for name in ["swift-exports-only.wasm", "swift-javascriptkit.wasm"] {
+        fs::write(wasm.join(name), fixture(name)).unwrap();
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .arg("--signatures")
+        .arg(starter())
+        .arg("--count-only")
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().next(), Some("{Swift: 2}"), "{stdout}");
+}
@@ -0,0 +1,62 @@
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use wasm_lang_inference::{classify, format_hints, parse_wasm_with, tally, ParseOptions};
+
+// Classifies every module in tests/fixtures and compares the per-file results and the summary histogram against a
+// checked-in snapshot, so that the corpus-wide impact of a heuristic change shows up in the PR diff. Run with
+// UPDATE_SNAPSHOTS=1 to accept the new output.
+#[test]
+fn fixture_corpus_matches_snapshot() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(root.join("fixtures"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+        .collect();
+    paths.sort();
+
+    let options = ParseOptions {
+        scan_code: true,
+        ..ParseOptions::default()
+    };
+    let mut actual = String::new();
+    let mut languages = vec![];
+    for path in &paths {
+        let buf = fs::read(path).unwrap();
+        let module = parse_wasm_with(&buf, &options);
+        let classification = classify(&module);
+        let name = path.file_name().unwrap().to_string_lossy();
+        write!(actual, "{name}: {:?}", classification.language).unwrap();
+        if !classification.hints.is_empty() {
+            write!(actual, ", {}", format_hints(&classification.hints)).unwrap();
+        }
+        if let Some(binding) = classification.js_binding {
+            write!(actual, ", js_binding={binding}").unwrap();
+        }
+        if !module.features().is_empty() {
+            write!(actual, ", features={}", module.features().join(",")).unwrap();
+        }
+        if module.partial {
+            write!(actual, ", partial").unwrap();
+        }
+        writeln!(actual).unwrap();
+        languages.push(classification.language);
+    }
+    writeln!(actual).unwrap();
+    writeln!(actual, "{:?}", tally(&languages)).unwrap();
+
+    let snapshot = root.join("snapshots").join("fixtures.txt");
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&snapshot, &actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&snapshot).unwrap_or_default();
+    assert!(
+        expected == actual,
+        "classification of the fixture corpus has changed, re-run with UPDATE_SNAPSHOTS=1 to accept\n\n\
+         --- expected\n{expected}\n+++ actual\n{actual}"
+    );
+}
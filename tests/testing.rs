@@ -0,0 +1,39 @@
+#![cfg(feature = "testing")]
+
+use wasm_lang_inference::testing::{assert_classification, ModuleBuilder};
+use wasm_lang_inference::{parse_wasm, Language, Type};
+
+#[test]
+fn built_modules_are_classified() {
+    let emscripten = ModuleBuilder::new()
+        .import_func("env", "emscripten_resize_heap", &[Type::I32], &[Type::I32])
+        .memory(256, Some("memory"))
+        .func(Some("main"), &[Type::I32, Type::I32], &[Type::I32])
+        .build();
+    assert_classification(&emscripten, Language::Emscripten);
+
+    let module = parse_wasm(&emscripten);
+    assert_eq!(module.imports[0].module, "env");
+    assert_eq!(module.exports.len(), 2);
+
+    let sections = ModuleBuilder::new()
+        .memory(1, None)
+        .data(b"hello")
+        .custom("extra", b"\x01\x02")
+        .build();
+    let module = parse_wasm(&sections);
+    assert!(!module.partial);
+    assert_eq!(module.custom_sections, ["extra"]);
+}
+
+#[test]
+#[should_panic(
+    expected = "expected Rust, but the module was classified as Emscripten (decided by emscripten.import)"
+)]
+fn mismatches_name_the_rules_that_matched() {
+    let module = ModuleBuilder::new()
+        .import_func("env", "emscripten_memcpy_big", &[], &[])
+        .func(Some("malloc"), &[Type::I32], &[Type::I32])
+        .build();
+    assert_classification(&module, Language::Rust);
+}
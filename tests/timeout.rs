@@ -0,0 +1,82 @@
+use std::env;
+use std::fs;
+use std::process::Command;
+use std::time::Instant;
+use wasm_lang_inference::{parse_wasm_with, ParseOptions};
+
+fn leb(mut n: usize, out: &mut Vec<u8>) {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn section(id: u8, contents: &[u8], out: &mut Vec<u8>) {
+    out.push(id);
+    leb(contents.len(), out);
+    out.extend(contents);
+}
+
+// a module with a single function of `nops` nop instructions, which takes a while to scan when there are millions
+fn long_function(nops: usize) -> Vec<u8> {
+    let mut body = vec![0];
+    body.resize(1 + nops, 0x01);
+    body.push(0x0b);
+    let mut code = vec![1];
+    leb(body.len(), &mut code);
+    code.extend(body);
+
+    let mut buf = b"\0asm\x01\0\0\0".to_vec();
+    section(1, &[1, 0x60, 0, 0], &mut buf);
+    section(3, &[1, 0], &mut buf);
+    section(10, &code, &mut buf);
+    buf
+}
+
+#[test]
+fn parsing_stops_at_the_deadline() {
+    let buf = long_function(10);
+    let options = ParseOptions {
+        scan_code: true,
+        deadline: Some(Instant::now()),
+        ..ParseOptions::default()
+    };
+    assert!(parse_wasm_with(&buf, &options).timed_out);
+    assert!(!parse_wasm_with(&buf, &ParseOptions::default()).timed_out);
+}
+
+// the scan carries on past the modules that time out, which are counted separately
+#[test]
+fn modules_over_the_timeout_are_skipped() {
+    let dir = env::temp_dir().join(format!(
+        "wasm-lang-inference-timeout-{}",
+        std::process::id()
+    ));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    fs::write(wasm.join("slow.wasm"), long_function(20_000_000)).unwrap();
+    fs::write(wasm.join("quick.wasm"), long_function(10)).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .args(["--scan-code", "--timeout", "1", "--workers", "1"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("quick.wasm"), "{stdout}");
+    assert!(!stdout.contains("slow.wasm"), "{stdout}");
+    assert!(
+        stdout.contains("1 files were skipped as they took longer than the --timeout to parse"),
+        "{stdout}"
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("slow.wasm: timed out"), "{stderr}");
+}
@@ -0,0 +1,73 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use wasm_lang_inference::{classify, parse_wasm};
+
+fn fixtures() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures"))
+}
+
+fn toolchain(name: &str) -> Option<String> {
+    let buf = fs::read(fixtures().join(name)).unwrap();
+    classify(&parse_wasm(&buf)).hints.get("toolchain").cloned()
+}
+
+// languages that are shared by several toolchains are split up by them
+#[test]
+fn languages_are_split_by_toolchain() {
+    assert_eq!(toolchain("c-wasi-main.wasm").as_deref(), Some("wasi-sdk"));
+    assert_eq!(
+        toolchain("emscripten-dylink.wasm").as_deref(),
+        Some("emscripten")
+    );
+    assert_eq!(toolchain("rust-wasi.wasm").as_deref(), Some("rustc"));
+    assert_eq!(
+        toolchain("rust-async.wasm").as_deref(),
+        Some("rustc+wasm-bindgen")
+    );
+    assert_eq!(toolchain("go-gojs.wasm").as_deref(), Some("go"));
+    assert_eq!(toolchain("go-tinygo.wasm").as_deref(), Some("tinygo"));
+    assert_eq!(
+        toolchain("go-tinygo-wasip1.wasm").as_deref(),
+        Some("tinygo")
+    );
+    assert_eq!(toolchain("debug-names.wasm"), None);
+}
+
+// --group-by toolchain gives the same counts with and without --count-only
+#[test]
+fn summary_groups_by_toolchain() {
+    let dir = env::temp_dir().join(format!(
+        "wasm-lang-inference-toolchain-{}",
+        std::process::id()
+    ));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&wasm).unwrap();
+    for name in [
+        "c-wasi-main.wasm",
+        "go-tinygo.wasm",
+        "rust-wasi.wasm",
+        "debug-names.wasm",
+    ] {
+        fs::copy(fixtures().join(name), wasm.join(name)).unwrap();
+    }
+
+    let run = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let full = run(&["--group-by", "toolchain"]);
+    let counts = run(&["--group-by", "toolchain", "--count-only"]);
+    fs::remove_dir_all(&dir).unwrap();
+
+    let expected = "{rustc: 1, tinygo: 1, unknown: 1, wasi-sdk: 1}";
+    assert!(full.lines().any(|line| line == expected), "{full}");
+    assert_eq!(counts.lines().next(), Some(expected), "{counts}");
+}
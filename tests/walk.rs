@@ -0,0 +1,33 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// A scan of a directory with entries that can't be read, here a broken symlink and a subdirectory, classifies the
+// modules that can be read and reports the rest, rather than panicking part way through.
+#[cfg(unix)]
+#[test]
+fn unreadable_entries_are_skipped() {
+    let dir = env::temp_dir().join(format!("wasm-lang-inference-walk-{}", std::process::id()));
+    let wasm = dir.join("wasm");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(wasm.join("subdirectory")).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    fs::copy(fixtures.join("go.wasm"), wasm.join("go.wasm")).unwrap();
+    std::os::unix::fs::symlink(dir.join("missing.wasm"), wasm.join("broken.wasm")).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .arg("--workers")
+        .arg("1")
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(stdout.contains("Go, ./wasm/go.wasm"), "{stdout}");
+    assert!(stdout.contains("1 files couldn't be read"), "{stdout}");
+    assert!(stderr.contains("skipping ./wasm/broken.wasm"), "{stderr}");
+}
@@ -0,0 +1,57 @@
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+// long enough for a file to be seen, found to be unchanged and classified, at 500ms a poll
+const SETTLE: Duration = Duration::from_millis(2000);
+
+// the files next to the modules in a build aren't reported, and the directory being deleted and recreated, as by a
+// clean build, doesn't stop the watch
+#[test]
+fn watch_skips_files_that_arent_wasm_and_survives_a_clean_build() {
+    let dir =
+        std::env::temp_dir().join(format!("wasm-lang-inference-watch-{}", std::process::id()));
+    let dist = dir.join("dist");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dist).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    fs::copy(fixtures.join("go.wasm"), dist.join("go.wasm")).unwrap();
+    fs::write(
+        dist.join("app.js"),
+        "WebAssembly.instantiateStreaming(fetch('go.wasm'))",
+    )
+    .unwrap();
+
+    let mut watch = Command::new(env!("CARGO_BIN_EXE_wasm-lang-inference"))
+        .args(["--watch", "dist"])
+        .current_dir(&dir)
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    thread::sleep(SETTLE);
+    fs::remove_dir_all(&dist).unwrap();
+    thread::sleep(SETTLE);
+    fs::create_dir_all(&dist).unwrap();
+    fs::copy(fixtures.join("julia.wasm"), dist.join("julia.wasm")).unwrap();
+    thread::sleep(SETTLE);
+    let running = watch.try_wait().unwrap().is_none();
+    watch.kill().unwrap();
+    watch.wait().unwrap();
+    let mut output = String::new();
+    watch
+        .stdout
+        .take()
+        .unwrap()
+        .read_to_string(&mut output)
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(running, "{output}");
+    assert!(output.contains("Go, dist/go.wasm"), "{output}");
+    assert!(output.contains("removed, dist/go.wasm"), "{output}");
+    assert!(output.contains("Julia, dist/julia.wasm"), "{output}");
+    assert!(!output.contains("app.js"), "{output}");
+}